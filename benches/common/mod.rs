@@ -0,0 +1,41 @@
+use aoc2023::{
+    answers::{Manifest, MANIFEST_FILE},
+    input::{InputSource, PUZZLE_SET},
+    solution::Part,
+};
+use std::path::Path;
+
+/// The input to benchmark a day with.
+pub struct BenchInput {
+    pub set: &'static str,
+    pub input: String,
+    /// The parts that can be run against the input.
+    pub parts: Vec<Part>,
+}
+
+/// Loads the input to benchmark a day with. The puzzle input is used if it
+/// can be found, otherwise the worked example is used so that every day still
+/// gets benchmarked on any checkout. Some parts don't work on the example, so
+/// only the parts with an answer for it in the manifest are run.
+pub fn bench_input(day: u8) -> Option<BenchInput> {
+    if let Ok(input) = InputSource::for_set(PUZZLE_SET, day, None).load(day) {
+        return Some(BenchInput {
+            set: PUZZLE_SET,
+            input,
+            parts: Part::ALL.to_vec(),
+        });
+    }
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(MANIFEST_FILE);
+    let manifest = Manifest::load(&path).unwrap_or_else(|err| panic!("{}", err));
+    let parts: Vec<Part> = Part::ALL
+        .into_iter()
+        .filter(|&part| manifest.get("example", day, part).is_some())
+        .collect();
+    let input = InputSource::for_set("example", day, None).load(day).ok()?;
+    Some(BenchInput {
+        set: "example",
+        input,
+        parts,
+    })
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use aoc2023::{days::DAYS, solution::Answer};
+use common::{bench_input, BenchInput};
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+/// Benchmarks parsing and each part of every day. The benchmark IDs are
+/// `dayN/<step>/<set>`, so a single day can be run with e.g.
+/// `cargo bench --bench days -- day17/`.
+fn bench_days(c: &mut Criterion) {
+    for day in DAYS.iter() {
+        let Some(BenchInput { set, input, parts }) = bench_input(day.number) else {
+            eprintln!("Skipping day {}, no input found.", day.number);
+            continue;
+        };
+        let solution = match (day.parse)(&input) {
+            Ok(solution) => solution,
+            Err(err) => {
+                eprintln!("Skipping day {}: {}", day.number, err);
+                continue;
+            }
+        };
+
+        let mut group = c.benchmark_group(format!("day{}", day.number));
+        group.bench_function(BenchmarkId::new("parse", set), |b| {
+            b.iter(|| (day.parse)(black_box(&input)))
+        });
+        for part in parts {
+            if solution.solve(part) == Answer::Unsolved {
+                continue;
+            }
+            group.bench_function(
+                BenchmarkId::new(format!("part{}", part.number()), set),
+                |b| b.iter(|| solution.solve(black_box(part))),
+            );
+        }
+        group.finish();
+    }
+}
+
+criterion_group! {
+    name = benches;
+    // Some of the days take seconds per run, so keep the sample count down.
+    config = Criterion::default().sample_size(10);
+    targets = bench_days
+}
+criterion_main!(benches);
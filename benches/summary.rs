@@ -0,0 +1,111 @@
+mod common;
+
+use aoc2023::{
+    days::{Day, DAYS},
+    solution::Part,
+};
+use common::{bench_input, BenchInput};
+use std::time::{Duration, Instant};
+
+/// How long to spend repeating each day to get a steady timing.
+const TIME_BUDGET: Duration = Duration::from_secs(1);
+
+/// The most times that each day is run.
+const MAX_RUNS: usize = 100;
+
+/// The timings for a single day, taking the median of each over all the runs.
+struct DayTiming {
+    day: u8,
+    set: &'static str,
+    runs: usize,
+    parse: Duration,
+    parts: Vec<(Part, Duration)>,
+}
+
+impl DayTiming {
+    fn part(&self, part: Part) -> String {
+        match self.parts.iter().find(|(p, _)| *p == part) {
+            Some((_, time)) => format!("{:.1?}", time),
+            None => "-".to_string(),
+        }
+    }
+
+    fn total(&self) -> Duration {
+        self.parse + self.parts.iter().map(|(_, time)| *time).sum::<Duration>()
+    }
+}
+
+fn median(mut times: Vec<Duration>) -> Duration {
+    times.sort();
+    times[times.len() / 2]
+}
+
+/// Runs a day until the time budget is used up, or it has been run enough
+/// times. Slow days are only run once.
+fn time_day(day: &Day, bench: &BenchInput) -> Option<DayTiming> {
+    let start = Instant::now();
+    let mut parse_times = vec![];
+    let mut part_times: Vec<Vec<Duration>> = vec![vec![]; bench.parts.len()];
+    while parse_times.len() < MAX_RUNS && (parse_times.is_empty() || start.elapsed() < TIME_BUDGET)
+    {
+        let run_start = Instant::now();
+        let solution = match (day.parse)(&bench.input) {
+            Ok(solution) => solution,
+            Err(err) => {
+                eprintln!("Skipping day {}: {}", day.number, err);
+                return None;
+            }
+        };
+        parse_times.push(run_start.elapsed());
+
+        for (times, &part) in part_times.iter_mut().zip(&bench.parts) {
+            let part_start = Instant::now();
+            solution.solve(part);
+            times.push(part_start.elapsed());
+        }
+    }
+
+    Some(DayTiming {
+        day: day.number,
+        set: bench.set,
+        runs: parse_times.len(),
+        parse: median(parse_times),
+        parts: bench
+            .parts
+            .iter()
+            .copied()
+            .zip(part_times.into_iter().map(median))
+            .collect(),
+    })
+}
+
+/// Times every day and ranks them from slowest to fastest, so that it is
+/// clear where the time goes. Run with `cargo bench --bench summary`.
+fn main() {
+    let mut timings: Vec<DayTiming> = DAYS
+        .iter()
+        .filter_map(|day| time_day(day, &bench_input(day.number)?))
+        .collect();
+    timings.sort_by_key(|timing| std::cmp::Reverse(timing.total()));
+
+    let total: Duration = timings.iter().map(DayTiming::total).sum();
+    println!(
+        "{:>4}  {:>4}  {:>10}  {:>10}  {:>10}  {:>10}  {:>6}  {:>4}  Input",
+        "Rank", "Day", "Parse", "Part 1", "Part 2", "Total", "Share", "Runs"
+    );
+    for (rank, timing) in timings.iter().enumerate() {
+        println!(
+            "{:>4}  {:>4}  {:>10}  {:>10}  {:>10}  {:>10}  {:>5.1}%  {:>4}  {}",
+            rank + 1,
+            timing.day,
+            format!("{:.1?}", timing.parse),
+            timing.part(Part::One),
+            timing.part(Part::Two),
+            format!("{:.1?}", timing.total()),
+            100.0 * timing.total().as_secs_f64() / total.as_secs_f64(),
+            timing.runs,
+            timing.set
+        );
+    }
+    println!("\nTotal: {:.1?}", total);
+}
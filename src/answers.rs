@@ -0,0 +1,197 @@
+use crate::solution::{Answer, Part};
+use serde::Deserialize;
+use std::{
+    collections::BTreeMap,
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+/// The manifest of expected answers that is checked in at the root of the
+/// repository.
+pub const MANIFEST_FILE: &str = "answers.toml";
+
+#[derive(Debug)]
+pub enum ManifestError {
+    /// The manifest could not be read.
+    Io { path: PathBuf, source: io::Error },
+    /// The manifest is not valid TOML, or has the wrong layout.
+    Toml(toml::de::Error),
+    /// A table in the manifest is not named `dayN`.
+    BadDay { set: String, key: String },
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestError::Io { path, source } => {
+                write!(f, "Unable to read {}: {}", path.display(), source)
+            }
+            ManifestError::Toml(err) => write!(f, "Invalid answers manifest: {}", err),
+            ManifestError::BadDay { set, key } => {
+                write!(
+                    f,
+                    "Expected `{}.dayN` in the answers manifest, found `{}.{}`",
+                    set, set, key
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ManifestError {}
+
+/// An answer as it is written in the manifest, which can either be a number or
+/// a string.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawAnswer {
+    Number(i64),
+    Text(String),
+}
+
+impl From<RawAnswer> for Answer {
+    fn from(raw: RawAnswer) -> Self {
+        match raw {
+            RawAnswer::Number(val) => Answer::from(val),
+            RawAnswer::Text(val) => Answer::from(val),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DayAnswers {
+    part1: Option<RawAnswer>,
+    part2: Option<RawAnswer>,
+}
+
+/// A single expected answer from the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected {
+    pub set: String,
+    pub day: u8,
+    pub part: Part,
+    pub answer: Answer,
+}
+
+/// The expected answers for each input set, day and part, e.g.
+///
+/// ```toml
+/// [input.day1]
+/// part1 = 53080
+/// part2 = 53268
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Manifest {
+    expected: Vec<Expected>,
+}
+
+impl Manifest {
+    pub fn load(path: &Path) -> Result<Manifest, ManifestError> {
+        let text = std::fs::read_to_string(path).map_err(|source| ManifestError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        Manifest::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Manifest, ManifestError> {
+        let sets: BTreeMap<String, BTreeMap<String, DayAnswers>> =
+            toml::from_str(text).map_err(ManifestError::Toml)?;
+
+        let mut expected = vec![];
+        for (set, days) in sets {
+            for (key, answers) in days {
+                let day = match key.strip_prefix("day").map(str::parse::<u8>) {
+                    Some(Ok(day)) => day,
+                    _ => {
+                        return Err(ManifestError::BadDay {
+                            set: set.clone(),
+                            key,
+                        })
+                    }
+                };
+
+                for (part, answer) in [(Part::One, answers.part1), (Part::Two, answers.part2)] {
+                    if let Some(answer) = answer {
+                        expected.push(Expected {
+                            set: set.clone(),
+                            day,
+                            part,
+                            answer: answer.into(),
+                        });
+                    }
+                }
+            }
+        }
+
+        // The days are keyed as strings, so put them back into number order.
+        expected.sort_by_key(|exp| (exp.day, exp.set.clone(), exp.part.number()));
+        Ok(Manifest { expected })
+    }
+
+    /// All of the expected answers, ordered by day, set and part.
+    pub fn expected(&self) -> &[Expected] {
+        &self.expected
+    }
+
+    pub fn get(&self, set: &str, day: u8, part: Part) -> Option<&Answer> {
+        self.expected
+            .iter()
+            .find(|exp| exp.set == set && exp.day == day && exp.part == part)
+            .map(|exp| &exp.answer)
+    }
+}
+
+/// Looks up the expected answer to a day's puzzle input for the tests.
+#[cfg(test)]
+pub(crate) fn test_answer(day: u8, part: Part) -> Answer {
+    use crate::input::PUZZLE_SET;
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(MANIFEST_FILE);
+    let manifest = Manifest::load(&path).unwrap_or_else(|err| panic!("{}", err));
+    match manifest.get(PUZZLE_SET, day, part) {
+        Some(answer) => answer.clone(),
+        None => panic!(
+            "No answer for day {} part {} in {}",
+            day,
+            part.number(),
+            MANIFEST_FILE
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_parse() {
+        let manifest = Manifest::parse(
+            "[input.day10]\npart1 = 6682\n\n[input.day2]\npart1 = 2348\npart2 = \"abc\"\n",
+        )
+        .unwrap();
+
+        let days: Vec<(u8, u8)> = manifest
+            .expected()
+            .iter()
+            .map(|exp| (exp.day, exp.part.number()))
+            .collect();
+        assert_eq!(days, vec![(2, 1), (2, 2), (10, 1)]);
+        assert_eq!(
+            manifest.get("input", 2, Part::Two),
+            Some(&Answer::from("abc"))
+        );
+        assert_eq!(manifest.get("input", 10, Part::Two), None);
+    }
+
+    #[test]
+    fn manifest_bad_day() {
+        let err = Manifest::parse("[input.first]\npart1 = 1\n").err().unwrap();
+
+        assert_eq!(
+            err.to_string(),
+            "Expected `input.dayN` in the answers manifest, found `input.first`"
+        );
+    }
+}
@@ -1,16 +1,30 @@
-#[cfg(test)]
-mod tests {
+use crate::{
+    error::ParseError,
+    solution::{Answer, Solution},
+};
 
-    use aoctk::io::read_string_col;
-    use std::path::Path;
+const TEXTUAL_DIGITS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const NUMERICAL_DIGITS: [&str; 9] = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
 
-    #[test]
-    fn day1_part1() {
-        // First we read in the lines of the input file.
-        let lines = read_string_col(Path::new("data/day1/data.txt")).unwrap();
+pub struct Day1 {
+    lines: Vec<String>,
+}
+
+impl Solution for Day1 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Day1 {
+            lines: input.lines().map(str::to_string).collect(),
+        })
+    }
 
+    fn part1(&self) -> Answer {
         // Find the integers in the lines.
-        let answer: u32 = lines
+        // A line without any digits has no calibration value, so there is no
+        // answer.
+        let answer: Option<u32> = self
+            .lines
             .iter()
             .map(|line| {
                 let digits = line
@@ -19,34 +33,24 @@ mod tests {
                     .collect::<Vec<u32>>();
 
                 // Combine the found numberic digits into integers.
-                let first_digit = digits.first().unwrap();
-                let second_digit = digits.last().unwrap();
+                let first_digit = digits.first()?;
+                let second_digit = digits.last()?;
 
                 // Assemble to find the number.
-                let found_number = first_digit * 10 + second_digit;
-                found_number
+                Some(first_digit * 10 + second_digit)
             })
             .sum();
 
-        // Check the answer.
-        assert_eq!(answer, 53080);
+        answer.map_or(Answer::Unsolved, Answer::from)
     }
 
-    #[test]
-    fn day1_part2() {
-        /// This is the same problem as part 1, with the exception that we now have to
-        /// detect textual numbers as well as just digits. I think we can implement
-        /// this inside the main digit loop with a basic state machine.
-        const TEXTUAL_DIGITS: [&str; 9] = [
-            "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-        ];
-        const NUMERICAL_DIGITS: [&str; 9] = ["1", "2", "3", "4", "5", "6", "7", "8", "9"];
-
-        // First we read in the lines of the input file.
-        let lines = read_string_col(Path::new("data/day1/data.txt")).unwrap();
-
+    /// This is the same problem as part 1, with the exception that we now have to
+    /// detect textual numbers as well as just digits. I think we can implement
+    /// this inside the main digit loop with a basic state machine.
+    fn part2(&self) -> Answer {
         // Find the integers in the lines.
-        let answer: u32 = lines
+        let answer: Option<u32> = self
+            .lines
             .iter()
             .map(|line| {
                 let mut digits: Vec<u32> = Vec::new();
@@ -74,18 +78,64 @@ mod tests {
                 let sortidx = aoctk::sorting::argsort(digit_indices.as_slice());
                 digits = sortidx.iter().map(|&idx| digits[idx]).collect();
 
-                println!("Digits: {:?}", digits);
                 // Combine the found numberic digits into integers.
-                let first_digit = digits.first().unwrap();
-                let second_digit = digits.last().unwrap();
+                let first_digit = digits.first()?;
+                let second_digit = digits.last()?;
 
                 // Assemble to find the number.
-                let found_number = first_digit * 10 + second_digit;
-                found_number
+                Some(first_digit * 10 + second_digit)
             })
             .sum();
 
+        answer.map_or(Answer::Unsolved, Answer::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day1_part1() {
+        // First we read in the lines of the input file.
+        let Some(input) = test_input(1) else {
+            return;
+        };
+
+        // Check the answer.
+        assert_eq!(
+            Day1::parse(&input).unwrap().part1(),
+            test_answer(1, Part::One)
+        );
+    }
+
+    #[test]
+    fn day1_part2() {
+        // First we read in the lines of the input file.
+        let Some(input) = test_input(1) else {
+            return;
+        };
+
         // Check the answer.
-        assert_eq!(answer, 53268);
+        assert_eq!(
+            Day1::parse(&input).unwrap().part2(),
+            test_answer(1, Part::Two)
+        );
+    }
+
+    #[test]
+    fn lines_without_digits() {
+        // The part 2 example spells out some of its digits, so not every line
+        // has a value in part 1.
+        let day = Day1::parse(include_str!("../../samples/day1/example2.txt")).unwrap();
+        assert_eq!(day.part1(), Answer::Unsolved);
+        assert_eq!(day.part2(), Answer::from(281));
+
+        assert_eq!(
+            Day1::parse("a1b\nnothing").unwrap().part2(),
+            Answer::Unsolved
+        );
     }
 }
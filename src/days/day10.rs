@@ -1,149 +1,408 @@
-pub fn find_start(grid: &Vec<Vec<char>>) -> (usize, usize) {
-    for (i, row) in grid.iter().enumerate() {
-        for (j, col) in row.iter().enumerate() {
-            if *col == 'S' {
-                return (i, j);
+use crate::{
+    error::ParseError,
+    grid::{Coord, Grid, ORTHOGONAL},
+    image::Rgb,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 10;
+
+const NORTH: Coord = (-1, 0);
+const EAST: Coord = (0, 1);
+const SOUTH: Coord = (1, 0);
+const WEST: Coord = (0, -1);
+
+/// Each pipe along with the two steps it connects.
+const PIPES: [(char, [Coord; 2]); 6] = [
+    ('|', [NORTH, SOUTH]),
+    ('-', [EAST, WEST]),
+    ('L', [NORTH, EAST]),
+    ('J', [NORTH, WEST]),
+    ('7', [SOUTH, WEST]),
+    ('F', [EAST, SOUTH]),
+];
+
+fn pipe_steps(tile: char) -> Option<[Coord; 2]> {
+    PIPES.iter().find(|(pipe, _)| *pipe == tile).map(|(_, steps)| *steps)
+}
+
+fn pipe_between(a: Coord, b: Coord) -> Option<char> {
+    PIPES.iter()
+        .find(|(_, steps)| steps.contains(&a) && steps.contains(&b) && a != b)
+        .map(|(pipe, _)| *pipe)
+}
+
+fn box_drawing(tile: char) -> char {
+    match tile {
+        '|' => '│',
+        '-' => '─',
+        'L' => '└',
+        'J' => '┘',
+        '7' => '┐',
+        'F' => '┌',
+        _ => ' ',
+    }
+}
+
+const ANSI_LOOP: &str = "\x1b[1;33m";
+const ANSI_INSIDE: &str = "\x1b[32m";
+const ANSI_OUTSIDE: &str = "\x1b[2m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+const BACKGROUND: Rgb = [24, 24, 32];
+const LOOP_COLOUR: Rgb = [255, 200, 40];
+const INSIDE_COLOUR: Rgb = [60, 160, 80];
+const OUTSIDE_COLOUR: Rgb = [90, 90, 100];
+
+/// The offsets from the centre of a tile's 3x3 block to each of its pixels.
+const BLOCK: [Coord; 9] = [
+    (-1, -1), (-1, 0), (-1, 1),
+    (0, -1), (0, 0), (0, 1),
+    (1, -1), (1, 0), (1, 1),
+];
+
+fn add(pos: Coord, step: Coord) -> Coord {
+    (pos.0 + step.0, pos.1 + step.1)
+}
+
+fn error_at(input: &str, pos: Coord, message: &str) -> ParseError {
+    let line = input.lines().nth(pos.0 as usize).unwrap_or_default();
+    let offset = line.char_indices().nth(pos.1 as usize).map_or(0, |(offset, _)| offset);
+    let tile = line.get(offset..offset + 1).unwrap_or_default();
+    ParseError::new(DAY, message).on_line(pos.0 as usize + 1).at(line, tile)
+}
+
+/// Follows the pipes from `start`, taking `first` as the first step, until
+/// they lead back to `start`. Gives the tiles on the loop in order along with
+/// the step that was taken back into `start`, or the first tile that doesn't
+/// connect to the one before it.
+fn trace_loop(
+    tiles: &Grid<char>,
+    start: Coord,
+    first: Coord,
+) -> Result<(Vec<Coord>, Coord), Coord> {
+    let mut path = vec![start];
+    let (mut pos, mut step) = (add(start, first), first);
+    while pos != start {
+        let back = (-step.0, -step.1);
+        let Some(steps) = tiles.get(pos)
+            .and_then(|tile| pipe_steps(*tile))
+            .filter(|steps| steps.contains(&back)) else {
+            return Err(pos);
+        };
+        path.push(pos);
+        step = if steps[0] == back { steps[1] } else { steps[0] };
+        pos = add(pos, step);
+    }
+    Ok((path, step))
+}
+
+/// Where a tile is in relation to the loop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TileKind {
+    Loop,
+    Inside,
+    Outside,
+}
+
+/// A map of pipes, with the loop that runs through the starting tile.
+#[derive(Debug, Clone)]
+pub struct PipeMap {
+    /// The tiles, with the starting tile replaced by the pipe under it.
+    tiles: Grid<char>,
+    start: Coord,
+    /// Every tile on the loop in order, beginning with the start.
+    path: Vec<Coord>,
+}
+
+impl PipeMap {
+    pub fn parse(input: &str) -> Result<PipeMap, ParseError> {
+        let mut tiles = Grid::parse_chars(DAY, input, "|-LJ7F.S")?;
+        let starts: Vec<Coord> = tiles.find_all(&'S').take(2).collect();
+        let start = match starts[..] {
+            [start] => start,
+            [] => return Err(ParseError::new(DAY, "the map has no starting tile `S`")),
+            [_, other, ..] => {
+                return Err(error_at(input, other, "the map has more than one starting tile"))
             }
+        };
+
+        // The pipe under the start is whichever one joins up two of its
+        // neighbours into a loop.
+        let (path, pipe) = ORTHOGONAL.iter()
+            .filter_map(|&first| {
+                let (path, last) = trace_loop(&tiles, start, first).ok()?;
+                let pipe = pipe_between(first, (-last.0, -last.1))?;
+                Some((path, pipe))
+            })
+            .next()
+            .ok_or_else(|| error_at(input, start, "the starting tile is not on a loop of pipes"))?;
+
+        tiles[start] = pipe;
+        Ok(PipeMap { tiles, start, path })
+    }
+
+    /// The tiles, with the starting tile replaced by the pipe under it.
+    pub fn tiles(&self) -> &Grid<char> {
+        &self.tiles
+    }
+
+    pub fn start(&self) -> Coord {
+        self.start
+    }
+
+    /// The pipe that is under the starting tile.
+    pub fn start_pipe(&self) -> char {
+        self.tiles[self.start]
+    }
+
+    /// Every tile on the loop in the order that they are joined, beginning
+    /// with the start.
+    pub fn path(&self) -> &[Coord] {
+        &self.path
+    }
+
+    /// The number of steps to the point on the loop farthest from the start.
+    pub fn farthest(&self) -> usize {
+        self.path.len() / 2
+    }
+
+    /// The fewest steps along the loop from the start to each tile, or `None`
+    /// for tiles that are not on the loop.
+    pub fn steps_from_start(&self) -> Grid<Option<usize>> {
+        let mut steps = Grid::new(self.tiles.height(), self.tiles.width(), None);
+        for (i, pos) in self.path.iter().enumerate() {
+            steps[*pos] = Some(i.min(self.path.len() - i));
         }
+        steps
+    }
+
+    /// Which tiles are on the loop.
+    pub fn on_loop(&self) -> Grid<bool> {
+        self.steps_from_start().map(Option::is_some)
+    }
+
+    /// The corners of the loop in order, which are the vertices of the
+    /// polygon that it makes.
+    pub fn vertices(&self) -> Vec<Coord> {
+        self.path.iter()
+            .filter(|pos| !matches!(self.tiles[**pos], '|' | '-'))
+            .copied()
+            .collect()
     }
-    panic!("No start found!");
-}
 
-fn next_pos(grid: &Vec<Vec<char>>, pos: &(usize, usize), prev: &(usize, usize)) -> (usize, usize) {
-    match grid[pos.0][pos.1] {
-        '|' => if prev.0 < pos.0 {
-            (pos.0 + 1, pos.1)
-        } else {
-            (pos.0 - 1, pos.1)
-        },
-        '-' => if prev.1 < pos.1 {
-            (pos.0, pos.1 + 1)
-        } else {
-            (pos.0, pos.1 - 1)
-        },
-        'L' => if prev.0 < pos.0 {
-            (pos.0, pos.1 + 1)
-        } else {
-            (pos.0 - 1, pos.1)
-        },
-        'J' => if prev.1 < pos.1 {
-            (pos.0 - 1, pos.1)
-        } else {
-            (pos.0, pos.1 - 1)
-        },
-        '7' => if prev.1 < pos.1 {
-            (pos.0 + 1, pos.1)
-        } else {
-            (pos.0, pos.1 - 1)
-        },
-        'F' => if prev.1 > pos.1 {
-            (pos.0 + 1, pos.1)
-        } else {
-            (pos.0, pos.1 + 1)
-        },
-        'S' => {
-            let neighbours = aoctk::grid::GridNeighbours::get_neighbours_for_coord(grid, pos.0, pos.1);
-            if  neighbours.w.is_some() && ['-', 'L', 'F'].contains(&neighbours.w.unwrap()) {
-                (pos.0, pos.1 - 1)
-            } else if neighbours.s.is_some() && ['|', 'J', 'L'].contains(&neighbours.s.unwrap()) {
-                (pos.0 + 1, pos.1)
-            } else if neighbours.e.is_some() && ['-', 'J', '7'].contains(&neighbours.e.unwrap()) {
-                    (pos.0, pos.1 + 1)
-            } else if neighbours.n.is_some() && ['|', 'F', '7'].contains(&neighbours.n.unwrap()) {
-                (pos.0 - 1, pos.1)
-            } else {
-                panic!("No neighbours!")
+    /// The number of tiles enclosed by the loop.
+    pub fn enclosed(&self) -> usize {
+        // The shoelace formula gives the area of the polygon through the
+        // middle of each tile on the loop. By Pick's theorem, that area is
+        // `inside + boundary / 2 - 1`, where the boundary is the loop itself.
+        let vertices = self.vertices();
+        let twice_area = vertices.iter()
+            .zip(vertices.iter().cycle().skip(1))
+            .map(|(a, b)| a.1 * b.0 - b.1 * a.0)
+            .sum::<i64>()
+            .abs();
+        ((twice_area - self.path.len() as i64) / 2 + 1) as usize
+    }
+
+    /// Sorts every tile into whether it is on, inside or outside the loop.
+    pub fn classify(&self) -> Grid<TileKind> {
+        let on_loop = self.on_loop();
+        let mut kinds = Grid::new(self.tiles.height(), self.tiles.width(), TileKind::Outside);
+        for i in 0..self.tiles.height() as i64 {
+            // Crossing a pipe that goes north along the row moves between the
+            // outside and the inside.
+            let mut inside = false;
+            for j in 0..self.tiles.width() as i64 {
+                if on_loop[(i, j)] {
+                    kinds[(i, j)] = TileKind::Loop;
+                    inside ^= matches!(self.tiles[(i, j)], '|' | 'L' | 'J');
+                } else if inside {
+                    kinds[(i, j)] = TileKind::Inside;
+                }
             }
         }
-        '.' => panic!("Ended in the ground!"),
-        _ => panic!("Invalid character!"),
+        kinds
     }
 
-}
+    /// Draws the map with box-drawing characters, shading the tiles inside
+    /// the loop. With `ansi` set the loop is drawn in bold yellow, the inside
+    /// in green, and the pipes outside the loop are dimmed. Without it, only
+    /// the loop and the inside are drawn.
+    pub fn render(&self, ansi: bool) -> String {
+        let kinds = self.classify();
+        let mut out = String::new();
+        for i in 0..self.tiles.height() as i64 {
+            let mut style = "";
+            for j in 0..self.tiles.width() as i64 {
+                let pipe = box_drawing(self.tiles[(i, j)]);
+                let (tile_style, c) = match kinds[(i, j)] {
+                    TileKind::Loop => (ANSI_LOOP, pipe),
+                    TileKind::Inside => (ANSI_INSIDE, '░'),
+                    TileKind::Outside if ansi => (ANSI_OUTSIDE, pipe),
+                    TileKind::Outside => ("", ' '),
+                };
+                if ansi && tile_style != style {
+                    out.push_str(ANSI_RESET);
+                    out.push_str(tile_style);
+                    style = tile_style;
+                }
+                out.push(c);
+            }
+            if ansi {
+                out.push_str(ANSI_RESET);
+            }
+            out.push('\n');
+        }
+        out
+    }
 
-use std::io::Write;
-fn count_inside(grid: &Vec<Vec<char>>, boundary: &Vec<Vec<bool>>) -> usize {
-    let mut inside_map = aoctk::grid::new_grid::<usize>(grid.len(), grid[0].len());
-    let res = (0..grid.len())
-        .map(|i| {
-            (0..grid[0].len())
-                .map(|j| {
-                    let crossings = trace_boundary_ray(grid, boundary, &(i, j));
-                    if !boundary[i][j] && crossings % 2 == 1 {
-                        inside_map[i][j] = 1;
-                        1
-                    } else {
-                        0
+    /// Draws the map as an image, with each tile as a 3x3 block of pixels
+    /// that shows the shape of its pipe. The tiles inside the loop are
+    /// filled in.
+    pub fn to_image(&self) -> Grid<Rgb> {
+        let kinds = self.classify();
+        let mut image = Grid::new(self.tiles.height() * 3, self.tiles.width() * 3, BACKGROUND);
+        for (pos, kind) in kinds.iter() {
+            let centre = (pos.0 * 3 + 1, pos.1 * 3 + 1);
+            let colour = match kind {
+                TileKind::Loop => LOOP_COLOUR,
+                TileKind::Outside => OUTSIDE_COLOUR,
+                TileKind::Inside => {
+                    for offset in BLOCK {
+                        image[add(centre, offset)] = INSIDE_COLOUR;
                     }
-                })
-                .sum::<usize>()
-        })
-        .sum();
-    res
+                    continue;
+                }
+            };
+            if let Some(steps) = pipe_steps(self.tiles[pos]) {
+                image[centre] = colour;
+                for step in steps {
+                    image[add(centre, step)] = colour;
+                }
+            }
+        }
+        image
+    }
 }
 
-fn trace_boundary_ray(grid: &Vec<Vec<char>>, boundary: &Vec<Vec<bool>>, pos: &(usize, usize)) -> usize {
-    let i = pos.0 as i64;
-    let mut j = pos.1 as i64;
+pub struct Day10 {
+    pipe_map: PipeMap,
+}
 
-    let mut crossings: usize = 0;
-    while i >= 0 && j >= 0 && i < boundary.len() as i64 && j < boundary[0].len() as i64 {
-        if boundary[i as usize][j as usize] && ['|', 'J', 'L', 'S'].contains(&grid[i as usize][j as usize]) {
-            crossings += 1;
-        }
-        j -= 1;
+impl Solution for Day10 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Day10 { pipe_map: PipeMap::parse(input)? })
+    }
+
+    fn part1(&self) -> Answer {
+        self.pipe_map.farthest().into()
+    }
+
+    fn part2(&self) -> Answer {
+        self.pipe_map.enclosed().into()
     }
-    crossings
 }
 
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    const INPUT: &str = include_str!("../../data/day10/data.txt");
+    use crate::{answers::test_answer, input::test_input, solution::Part};
 
     #[test]
     fn day10_part1() {
-        let pipe_map = INPUT
-            .lines()
-            .map(|line| line.chars().collect::<Vec<char>>())
-            .collect::<Vec<Vec<char>>>();
-        
-        let dest = find_start(&pipe_map);
-        let  (mut i, mut j) = dest.clone();
-        let mut prev = dest.clone();
-        let mut dist = 0;
-        while (i, j) != dest || dist == 0 {
-            let (newi, newj) = next_pos(&pipe_map, &(i, j), &prev);
-            dist += 1;
-            prev = (i, j);
-            (i, j) = (newi, newj);
-        }
+        let Some(input) = test_input(10) else {
+            return;
+        };
 
-        assert_eq!(dist / 2, 6682);
+        assert_eq!(Day10::parse(&input).unwrap().part1(), test_answer(10, Part::One));
     }
 
     #[test]
     fn day10_part2() {
-        let pipe_map = INPUT
-            .lines()
-            .map(|line| line.chars().collect::<Vec<char>>())
-            .collect::<Vec<Vec<char>>>();
-        
-        let mut boundary_map = aoctk::grid::new_grid::<bool>(pipe_map.len(), pipe_map[0].len());
-        let dest = find_start(&pipe_map);
-        let  (mut i, mut j) = dest.clone();
-        let mut prev = dest.clone();
-        let mut dist = 0;
-        while (i, j) != dest || dist == 0 {
-            boundary_map[i][j] = true;
-            let (newi, newj) = next_pos(&pipe_map, &(i, j), &prev);
-            prev = (i, j);
-            dist += 1;
-            (i, j) = (newi, newj);
+        let Some(input) = test_input(10) else {
+            return;
+        };
+
+        assert_eq!(Day10::parse(&input).unwrap().part2(), test_answer(10, Part::Two));
+    }
+
+    #[test]
+    fn start_pipe_is_inferred() {
+        let pipe_map = PipeMap::parse(include_str!("../../samples/day10/example.txt")).unwrap();
+        assert_eq!(pipe_map.start(), (2, 0));
+        assert_eq!(pipe_map.start_pipe(), 'F');
+        assert_eq!(pipe_map.path().len(), 16);
+        assert_eq!(pipe_map.path()[..3], [(2, 0), (2, 1), (1, 1)]);
+        assert_eq!(pipe_map.farthest(), 8);
+
+        let steps = pipe_map.steps_from_start();
+        assert_eq!(steps[(2, 4)], Some(8));
+        assert_eq!(steps[(3, 0)], Some(1));
+        assert_eq!(steps[(0, 0)], None);
+
+        // The `-` to the west of the start doesn't lead anywhere.
+        let pipe_map = PipeMap::parse("-S7\n.LJ").unwrap();
+        assert_eq!(pipe_map.start_pipe(), 'F');
+        assert_eq!(pipe_map.path(), [(0, 1), (0, 2), (1, 2), (1, 1)]);
+    }
+
+    #[test]
+    fn malformed_maps() {
+        let err = Day10::parse("F7.\nLJ.").err().unwrap();
+        assert_eq!(err.message, "the map has no starting tile `S`");
+
+        let err = Day10::parse("S7.\nLJS").err().unwrap();
+        assert_eq!((err.line, err.column), (2, 3));
+
+        let err = Day10::parse("S7.\nL-.").err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 1, "S"));
+    }
+
+    #[test]
+    fn enclosed_tiles() {
+        let examples = [
+            (include_str!("../../samples/day10/example2.txt"), 4),
+            (include_str!("../../samples/day10/example3.txt"), 8),
+            (include_str!("../../samples/day10/example4.txt"), 10),
+        ];
+        for (input, expected) in examples {
+            let pipe_map = PipeMap::parse(input).unwrap();
+            assert_eq!(pipe_map.enclosed(), expected);
+            let kinds = pipe_map.classify();
+            assert_eq!(kinds.find_all(&TileKind::Inside).count(), expected);
+            assert_eq!(kinds.find_all(&TileKind::Loop).count(), pipe_map.path().len());
         }
-        let area = count_inside(&pipe_map, &boundary_map);
-       
-        assert_eq!(area + 1, 353); // Too High. 
+
+        let pipe_map = PipeMap::parse(include_str!("../../samples/day10/example2.txt")).unwrap();
+        assert_eq!(pipe_map.vertices(), [(1, 1), (1, 9), (7, 9), (7, 6), (5, 6), (5, 8),
+            (2, 8), (2, 2), (5, 2), (5, 4), (7, 4), (7, 1)]);
+        let kinds = pipe_map.classify();
+        assert_eq!(kinds[(6, 2)], TileKind::Inside);
+        assert_eq!(kinds[(6, 5)], TileKind::Outside);
+        assert_eq!(kinds[(3, 3)], TileKind::Outside);
+    }
+
+    #[test]
+    fn render_the_loop() {
+        let pipe_map = PipeMap::parse("-S-7.\n.|.|.\n.L-J.").unwrap();
+        assert_eq!(pipe_map.render(false), " ┌─┐ \n │░│ \n └─┘ \n");
+
+        let coloured = pipe_map.render(true);
+        assert!(coloured.starts_with("\x1b[0m\x1b[2m─\x1b[0m\x1b[1;33m┌─┐"));
+        assert!(coloured.contains("\x1b[0m\x1b[32m░"));
+
+        let image = pipe_map.to_image();
+        assert_eq!((image.height(), image.width()), (9, 15));
+        assert_eq!(image[(4, 7)], INSIDE_COLOUR);
+        assert_eq!(image[(3, 6)], INSIDE_COLOUR);
+        assert_eq!(image[(5, 8)], INSIDE_COLOUR);
+        // The start is an `F`, so it joins to the east and the south.
+        assert_eq!(image[(1, 4)], LOOP_COLOUR);
+        assert_eq!(image[(1, 5)], LOOP_COLOUR);
+        assert_eq!(image[(2, 4)], LOOP_COLOUR);
+        assert_eq!(image[(0, 4)], BACKGROUND);
+        assert_eq!(image[(1, 0)], OUTSIDE_COLOUR);
     }
-}
\ No newline at end of file
+}
@@ -1,101 +1,309 @@
+use crate::{
+    error::ParseError,
+    grid::Grid,
+    solution::{Answer, Solution},
+};
+use std::collections::BTreeMap;
+
+const DAY: u8 = 11;
+
+/// A galaxy's position in the image, before any expansion.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Galaxy {
-    i: i64,
-    j: i64,
+    pub i: i64,
+    pub j: i64,
 }
 
-pub fn nodes_with_expansion(lines: &Vec<Vec<char>>, expansion: i64) -> Vec<Galaxy> {
-    // We assemble a list of the indi that contain empty rows.
-    let i_expand: Vec<i64> = lines
-        .iter()
-        .enumerate()
-        .filter_map(|(no, line)| {
-            if !line.contains(&'#') {
-                Some(no as i64)
-            } else {
-                None
-            }
-        })
-        .collect();
-
-    // Do the same as above, but factoring in the empty columns.
-    let j_expand: Vec<i64> = (0..lines.len() - 1)
-        .map(|j| {
-            let col = lines.iter().map(|line| line[j]).all(|c| c != '#');
-
-            if col {
-                Some(j as i64)
-            } else {
-                None
-            }
+/// How many times larger each empty row and each empty column becomes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Expansion {
+    pub rows: i64,
+    pub cols: i64,
+}
+
+impl Expansion {
+    /// Expands the empty rows and columns by the same factor.
+    pub fn uniform(factor: i64) -> Expansion {
+        Expansion {
+            rows: factor,
+            cols: factor,
+        }
+    }
+}
+
+/// The positions of the galaxies along one axis of the image.
+#[derive(Debug, Clone)]
+struct Axis {
+    /// The position of each galaxy, and how many empty lines come before it.
+    positions: Vec<(i64, i64)>,
+    /// The galaxies in order along the axis. The order is the same for any
+    /// expansion, because expanding never moves one galaxy past another.
+    order: Vec<usize>,
+}
+
+impl Axis {
+    fn new(positions: Vec<i64>, size: usize) -> Axis {
+        let mut occupied = vec![false; size];
+        for pos in positions.iter() {
+            occupied[*pos as usize] = true;
+        }
+        // The number of empty lines before each line.
+        let empty_before: Vec<i64> = occupied
+            .iter()
+            .scan(0, |empty, occupied| {
+                let before = *empty;
+                *empty += i64::from(!occupied);
+                Some(before)
+            })
+            .collect();
+
+        let mut order: Vec<usize> = (0..positions.len()).collect();
+        order.sort_by_key(|k| positions[*k]);
+        Axis {
+            positions: positions
+                .into_iter()
+                .map(|pos| (pos, empty_before[pos as usize]))
+                .collect(),
+            order,
+        }
+    }
+
+    fn expanded(&self, k: usize, factor: i64) -> i64 {
+        let (pos, empty_before) = self.positions[k];
+        pos + empty_before * (factor - 1)
+    }
+
+    /// Sums the distances along the axis between every pair of galaxies.
+    fn distance_sum(&self, factor: i64) -> i64 {
+        // Each galaxy is the far end of a pair with every galaxy before it,
+        // so it adds its position that many times, less the sum of theirs.
+        let mut before = 0;
+        self.order
+            .iter()
+            .enumerate()
+            .map(|(count, k)| {
+                let pos = self.expanded(*k, factor);
+                let distances = pos * count as i64 - before;
+                before += pos;
+                distances
+            })
+            .sum()
+    }
+}
+
+/// The galaxies in an image, which can be expanded by any amount without
+/// having to find them again.
+#[derive(Debug, Clone)]
+pub struct GalaxyMap {
+    galaxies: Vec<Galaxy>,
+    rows: Axis,
+    cols: Axis,
+}
+
+impl GalaxyMap {
+    pub fn parse(input: &str) -> Result<GalaxyMap, ParseError> {
+        let image = Grid::parse_chars(DAY, input, ".#")?;
+        let galaxies: Vec<Galaxy> = image.find_all(&'#').map(|(i, j)| Galaxy { i, j }).collect();
+        Ok(GalaxyMap {
+            rows: Axis::new(galaxies.iter().map(|g| g.i).collect(), image.height()),
+            cols: Axis::new(galaxies.iter().map(|g| g.j).collect(), image.width()),
+            galaxies,
         })
-        .filter_map(std::convert::identity)
-        .collect();
-
-    // Calculate the indices of all of the galaxies in the data, factoring in the expansion.
-    let nodes: Vec<Galaxy> = lines
-        .iter()
-        .enumerate()
-        .map(|(i, line)| {
-            line.iter()
-                .enumerate()
-                .filter_map(|(j, c)| {
-                    if *c == '#' {
-                        let gali = i as i64
-                            + i_expand.iter().filter(|&&x| x < i as i64).count() as i64
-                                * (expansion - 1);
-                        let galj = j as i64
-                            + j_expand.iter().filter(|&&x| x < j as i64).count() as i64
-                                * (expansion - 1);
-                        Some(Galaxy {
-                            i: gali as i64,
-                            j: galj as i64,
-                        })
-                    } else {
-                        None
-                    }
-                })
-                .collect::<Vec<Galaxy>>()
+    }
+
+    /// The galaxies, row by row, before any expansion.
+    pub fn galaxies(&self) -> &[Galaxy] {
+        &self.galaxies
+    }
+
+    /// Where galaxy `k` ends up after the expansion.
+    pub fn expanded(&self, k: usize, expansion: Expansion) -> Galaxy {
+        Galaxy {
+            i: self.rows.expanded(k, expansion.rows),
+            j: self.cols.expanded(k, expansion.cols),
+        }
+    }
+
+    /// Sums the distances between every pair of galaxies after the
+    /// expansion. The distances along each axis are summed separately.
+    pub fn distance_sum(&self, expansion: Expansion) -> i64 {
+        self.rows.distance_sum(expansion.rows) + self.cols.distance_sum(expansion.cols)
+    }
+
+    /// The shortest path between galaxies `k` and `m` after the expansion.
+    pub fn distance(&self, k: usize, m: usize, expansion: Expansion) -> i64 {
+        let (a, b) = (self.expanded(k, expansion), self.expanded(m, expansion));
+        (a.i - b.i).abs() + (a.j - b.j).abs()
+    }
+
+    /// Every pair of galaxies `(k, m)` with `k < m`, along with the distance
+    /// between them. The distances are only worked out as they are needed.
+    pub fn distances(
+        &self,
+        expansion: Expansion,
+    ) -> impl Iterator<Item = (usize, usize, i64)> + '_ {
+        let n = self.galaxies.len();
+        (0..n)
+            .flat_map(move |k| (k + 1..n).map(move |m| (k, m)))
+            .map(move |(k, m)| (k, m, self.distance(k, m, expansion)))
+    }
+
+    /// The `count` galaxies closest to galaxy `k` after the expansion, with
+    /// their distances, nearest first. Galaxies that are the same distance
+    /// away are in the order that they appear in the image.
+    pub fn nearest(&self, k: usize, count: usize, expansion: Expansion) -> Vec<(usize, i64)> {
+        let mut others: Vec<(usize, i64)> = (0..self.galaxies.len())
+            .filter(|m| *m != k)
+            .map(|m| (m, self.distance(k, m, expansion)))
+            .collect();
+        let by_distance = |a: &(usize, i64), b: &(usize, i64)| (a.1, a.0).cmp(&(b.1, b.0));
+        if count < others.len() {
+            others.select_nth_unstable_by(count, by_distance);
+            others.truncate(count);
+        }
+        others.sort_unstable_by(by_distance);
+        others
+    }
+
+    /// How many pairs of galaxies are each distance apart after the
+    /// expansion.
+    pub fn distance_histogram(&self, expansion: Expansion) -> BTreeMap<i64, usize> {
+        let mut histogram = BTreeMap::new();
+        for (_, _, distance) in self.distances(expansion) {
+            *histogram.entry(distance).or_default() += 1;
+        }
+        histogram
+    }
+}
+
+pub struct Day11 {
+    galaxy_map: GalaxyMap,
+}
+
+impl Solution for Day11 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Day11 {
+            galaxy_map: GalaxyMap::parse(input)?,
         })
-        .flatten()
-        .collect();
-    nodes
+    }
+
+    fn part1(&self) -> Answer {
+        self.galaxy_map.distance_sum(Expansion::uniform(2)).into()
+    }
+
+    fn part2(&self) -> Answer {
+        // I saw this coming, so it was simply a case op uppering the expansion coefficient.
+        self.galaxy_map
+            .distance_sum(Expansion::uniform(1_000_000))
+            .into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use itertools::Itertools;
-    const INPUT: &str = include_str!("../../data/day11/input.txt");
+    use crate::{answers::test_answer, input::test_input, solution::Part};
 
     #[test]
     fn day11_part1() {
-        let input = INPUT
-            .lines()
-            .map(|line| line.chars().collect::<Vec<char>>())
-            .collect::<Vec<Vec<char>>>();
-        let nodes = nodes_with_expansion(&input, 2);
-        let dist_sum: i64 = nodes
-            .into_iter()
-            .combinations_with_replacement(2)
-            .map(|pair| (pair[0].i - pair[1].i).abs() + (pair[0].j - pair[1].j).abs())
-            .sum();
-        assert_eq!(dist_sum, 9509330);
+        let Some(input) = test_input(11) else {
+            return;
+        };
+
+        assert_eq!(
+            Day11::parse(&input).unwrap().part1(),
+            test_answer(11, Part::One)
+        );
     }
 
     #[test]
     fn day11_part2() {
-        let input = INPUT
-            .lines()
-            .map(|line| line.chars().collect::<Vec<char>>())
-            .collect::<Vec<Vec<char>>>();
-        // I saw this coming, so it was simply a case op uppering the expansion coefficient.
-        let nodes = nodes_with_expansion(&input, 1_000_000);
-        let dist_sum: i64 = nodes
-            .into_iter()
-            .combinations_with_replacement(2)
-            .map(|pair| (pair[0].i - pair[1].i).abs() + (pair[0].j - pair[1].j).abs())
-            .sum();
-        assert_eq!(dist_sum, 635832237682);
+        let Some(input) = test_input(11) else {
+            return;
+        };
+
+        assert_eq!(
+            Day11::parse(&input).unwrap().part2(),
+            test_answer(11, Part::Two)
+        );
+    }
+
+    #[test]
+    fn day11_example_expansions() {
+        let map = GalaxyMap::parse(include_str!("../../samples/day11/example.txt")).unwrap();
+
+        assert_eq!(map.distance_sum(Expansion::uniform(2)), 374);
+        assert_eq!(map.distance_sum(Expansion::uniform(10)), 1030);
+        assert_eq!(map.distance_sum(Expansion::uniform(100)), 8410);
+    }
+
+    /// Sums the distances by checking every pair, to compare against.
+    fn pairwise_sum(map: &GalaxyMap, expansion: Expansion) -> i64 {
+        map.distances(expansion)
+            .map(|(_, _, distance)| distance)
+            .sum()
+    }
+
+    #[test]
+    fn rectangular_images() {
+        // The last column is empty, and so is the middle row.
+        let map = GalaxyMap::parse("#..#.\n.....\n.#...").unwrap();
+        assert_eq!(
+            map.expanded(1, Expansion { rows: 3, cols: 5 }),
+            Galaxy { i: 0, j: 7 }
+        );
+        assert_eq!(
+            map.expanded(2, Expansion { rows: 3, cols: 5 }),
+            Galaxy { i: 4, j: 1 }
+        );
+
+        for expansion in [
+            Expansion::uniform(1),
+            Expansion { rows: 2, cols: 7 },
+            Expansion {
+                rows: 1_000,
+                cols: 1,
+            },
+        ] {
+            assert_eq!(map.distance_sum(expansion), pairwise_sum(&map, expansion));
+        }
+
+        let tall = GalaxyMap::parse(".#\n..\n..\n#.\n..").unwrap();
+        assert_eq!(tall.distance_sum(Expansion::uniform(2)), 6);
+    }
+
+    #[test]
+    fn distance_queries() {
+        let map = GalaxyMap::parse(include_str!("../../samples/day11/example.txt")).unwrap();
+        let twice = Expansion::uniform(2);
+
+        // The pairs picked out in the puzzle, which numbers the galaxies
+        // from 1.
+        assert_eq!(map.distance(4, 8, twice), 9);
+        assert_eq!(map.distance(0, 6, twice), 15);
+        assert_eq!(map.distance(2, 5, twice), 17);
+        assert_eq!(map.distance(7, 8, twice), 5);
+        assert_eq!(map.distance(8, 7, twice), 5);
+
+        assert_eq!(map.nearest(7, 2, twice), vec![(8, 5), (4, 6)]);
+        assert_eq!(map.nearest(7, 100, twice).len(), 8);
+        assert_eq!(map.nearest(7, 0, twice), vec![]);
+
+        let histogram = map.distance_histogram(twice);
+        assert_eq!(histogram.values().sum::<usize>(), 36);
+        assert_eq!(
+            histogram
+                .iter()
+                .map(|(d, count)| d * *count as i64)
+                .sum::<i64>(),
+            374
+        );
+
+        // Sweeping the expansion only moves the galaxies, without finding
+        // them again.
+        for factor in [1, 10, 1_000_000_000] {
+            let expansion = Expansion::uniform(factor);
+            assert_eq!(map.distance_sum(expansion), pairwise_sum(&map, expansion));
+        }
     }
 }
@@ -1,138 +1,230 @@
-use itertools::Itertools;
-use memoize::memoize;
-use regex::Regex;
-
-const BROKEN_SEPARATOR: &str = "[.]+";
-
-pub fn parse_input(input: &str) -> (Vec<String>, Vec<Vec<usize>>) {
-    let lines = input.lines().collect_vec();
-    lines
-        .iter()
-        .map(|line| {
-            let segs: (&str, &str) = line.split_once(" ").unwrap();
-            let broken_map = segs.0.to_string();
-            let broken_pattern: Vec<usize> = segs
-                .1
-                .split(",")
-                .map(|s| s.parse::<usize>().unwrap())
-                .collect();
-
-            (broken_map, broken_pattern)
-        })
-        .unzip()
+use crate::{
+    error::{parse_lines, parse_token, ParseError},
+    solution::{Answer, Solution},
+};
+use rayon::prelude::*;
+
+const DAY: u8 = 12;
+
+/// How many copies of each record are joined together in part 2.
+pub const UNFOLD_FACTOR: usize = 5;
+
+/// One row of the condition records: the springs, as `.` for working, `#` for
+/// broken and `?` for unknown, and the sizes of each group of broken springs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub springs: Vec<u8>,
+    pub groups: Vec<usize>,
 }
 
-pub fn is_valid_row(row: &str, broken_pattern: &Vec<usize>) -> bool {
-    let re = Regex::new(BROKEN_SEPARATOR).unwrap();
-    let groups: Vec<&str> = re.split(row).filter(|group| group.len() > 0).collect();
-    if groups.len() != broken_pattern.len() {
-        return false;
+impl Record {
+    /// Joins `factor` copies of the record together, with an unknown spring
+    /// between each copy of the springs.
+    pub fn unfold(&self, factor: usize) -> Record {
+        let springs = vec![self.springs.as_slice(); factor].join(&b'?');
+        Record {
+            springs,
+            groups: self.groups.repeat(factor),
+        }
     }
 
-    groups
-        .iter()
-        .zip(broken_pattern.iter())
-        .all(|(group, broken)| group.len() == *broken)
+    /// The number of ways that the unknown springs could be filled in to
+    /// match the groups.
+    pub fn count_arrangements(&self) -> u64 {
+        count_arrangements(&self.springs, &self.groups)
+    }
 }
 
-/// This is the brute-force approach that I originally solves part 1 with.
-/// This would not scale to part 2.
-#[memoize]
-fn count_valid_combinations(row: String, broken_pattern: Vec<usize>) -> usize {
-    let unknown_posns = row.match_indices("?").collect_vec();
-    if unknown_posns.len() > 0 {
-        let pos = unknown_posns.first().unwrap().0;
-        let mut working_test = row.to_string();
-        working_test.replace_range(pos..pos + 1, ".");
-        let mut broken_test = row.to_string();
-        broken_test.replace_range(pos..pos + 1, "#");
-
-        count_valid_combinations(working_test, broken_pattern.clone())
-            + count_valid_combinations(broken_test, broken_pattern.clone())
-    } else {
-        if is_valid_row(&row, &broken_pattern) {
-            1
-        } else {
-            0
+pub fn parse_input(input: &str) -> Result<Vec<Record>, ParseError> {
+    parse_lines(input, |line| {
+        let segs: (&str, &str) = line
+            .split_once(" ")
+            .ok_or_else(|| ParseError::new(DAY, "expected `<springs> <groups>`").at(line, line))?;
+        if let Some((offset, c)) = segs.0.char_indices().find(|(_, c)| !".#?".contains(*c)) {
+            return Err(
+                ParseError::new(DAY, "unexpected spring, expected one of `.#?`")
+                    .at(line, &segs.0[offset..offset + c.len_utf8()]),
+            );
+        }
+        let groups: Vec<usize> = segs
+            .1
+            .split(",")
+            .map(|s| parse_token(DAY, line, s))
+            .collect::<Result<Vec<usize>, ParseError>>()?;
+
+        Ok(Record {
+            springs: segs.0.as_bytes().to_vec(),
+            groups,
+        })
+    })
+}
+
+/// Counts the ways that the unknown springs could be filled in so that the
+/// broken ones make up `groups`, working back from the end of the row.
+pub fn count_arrangements(springs: &[u8], groups: &[usize]) -> u64 {
+    let (len, n) = (springs.len(), groups.len());
+    // `ways[i][k]` counts the arrangements of `springs[i..]` that match
+    // `groups[k..]`. A group that ends with the last spring is followed by
+    // the working spring just past the end, so the table goes up to
+    // `len + 1`.
+    let mut ways = vec![vec![0u64; n + 1]; len + 2];
+    ways[len][n] = 1;
+    ways[len + 1][n] = 1;
+
+    for i in (0..len).rev() {
+        for k in 0..=n {
+            // The spring is working, so the groups carry on from the next one.
+            let mut count = if springs[i] != b'#' {
+                ways[i + 1][k]
+            } else {
+                0
+            };
+
+            // The spring starts group `k`, which has to be followed by a
+            // working spring or the end of the row.
+            if springs[i] != b'.' && k < n {
+                let end = i + groups[k];
+                if end <= len && !springs[i..end].contains(&b'.') && springs.get(end) != Some(&b'#')
+                {
+                    count += ways[end + 1][k + 1];
+                }
+            }
+            ways[i][k] = count;
         }
     }
+    ways[0][0]
 }
 
-/// Thanks to https://www.youtube.com/watch?v=g3Ms5e7Jdqo for explaining
-/// this solution. Nice little logic puzzle.
-#[memoize]
-fn count_combinations(row: String, pattern: Vec<usize>) -> usize {
-    if row.chars().count() == 0 {
-        // If we have reached the end of the row,
-        // and we still have numbers to match, then it is not a valid combination.
-        return if pattern.len() == 0 { 1 } else { 0 };
+pub struct Day12 {
+    records: Vec<Record>,
+}
+
+impl Day12 {
+    /// Sums the arrangements of every record, after unfolding each one
+    /// `factor` times.
+    pub fn sum_arrangements(&self, factor: usize) -> u64 {
+        self.records
+            .par_iter()
+            .map(|record| record.unfold(factor).count_arrangements())
+            .sum()
     }
+}
 
-    if pattern.len() == 0 {
-        // If after all of the numbers we are left with any non-matched,
-        // then it is not a valid combination.
-        return if row.contains("#") { 0 } else { 1 };
+impl Solution for Day12 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let records = parse_input(input)?;
+        Ok(Day12 { records })
     }
 
-    let mut count = 0;
-    if ".?".contains(row.chars().nth(0).unwrap()) {
-        count += count_combinations(row.chars().skip(1).collect(), pattern.clone());
+    fn part1(&self) -> Answer {
+        self.sum_arrangements(1).into()
     }
 
-    if "#?".contains(row.chars().nth(0).unwrap()) {
-        if pattern[0] <= row.chars().count()
-            && row.chars().take(pattern[0]).all(|c| c != '.')
-            && (pattern[0] == row.chars().count() || row.chars().nth(pattern[0]).unwrap() != '#')
-        {
-            count += count_combinations(
-                row.chars().skip(pattern[0] + 1).collect(),
-                pattern[1..].to_vec(),
-            );
-        }
+    fn part2(&self) -> Answer {
+        self.sum_arrangements(UNFOLD_FACTOR).into()
     }
-    count
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use rayon::prelude::*;
-
-    const INPUT: &str = include_str!("../../data/day12/input.txt");
+    use crate::{answers::test_answer, input::test_input, solution::Part};
 
     #[test]
     fn day12_part1() {
-        let (broken_map, broken_pattern) = parse_input(INPUT);
-        let count: Vec<usize> = broken_map
-            .par_iter()
-            .zip(broken_pattern)
-            .map(|(row, pattern)| count_combinations(row.to_string(), pattern))
+        let Some(input) = test_input(12) else {
+            return;
+        };
+
+        assert_eq!(
+            Day12::parse(&input).unwrap().part1(),
+            test_answer(12, Part::One)
+        );
+    }
+
+    #[test]
+    fn day12_part2() {
+        let Some(input) = test_input(12) else {
+            return;
+        };
+
+        assert_eq!(
+            Day12::parse(&input).unwrap().part2(),
+            test_answer(12, Part::Two)
+        );
+    }
+
+    /// Checks whether the broken springs in a row with no unknowns make up
+    /// `groups`.
+    fn is_valid_row(row: &str, groups: &[usize]) -> bool {
+        let broken: Vec<usize> = row
+            .split('.')
+            .filter(|group| !group.is_empty())
+            .map(str::len)
             .collect();
+        broken == groups
+    }
+
+    #[test]
+    fn test_is_valid_row() {
+        assert!(is_valid_row("#.#.###", &[1, 1, 3]));
+        assert!(!is_valid_row("##..###", &[1, 1, 3]));
 
-        assert_eq!(count.iter().sum::<usize>(), 7939);
+        assert!(is_valid_row("#....######..#####.", &[1, 6, 5]));
+    }
+
+    /// This is the brute-force approach that I originally solved part 1 with,
+    /// trying both options for every unknown spring. It would not scale to
+    /// part 2.
+    fn count_valid_combinations(row: String, groups: &[usize]) -> u64 {
+        match row.find('?') {
+            Some(pos) => {
+                let working_test = row.replacen('?', ".", 1);
+                let broken_test = row[..pos].to_string() + "#" + &row[pos + 1..];
+                count_valid_combinations(working_test, groups)
+                    + count_valid_combinations(broken_test, groups)
+            }
+            None => u64::from(is_valid_row(&row, groups)),
+        }
     }
 
     #[test]
-    fn day12_part2() {
-        let (broken_map, broken_pattern) = parse_input(INPUT);
-        let count: Vec<usize> = broken_map
+    fn arrangements_per_row() {
+        let day = Day12::parse(include_str!("../../samples/day12/example.txt")).unwrap();
+        let folded: Vec<u64> = day.records.iter().map(Record::count_arrangements).collect();
+        assert_eq!(folded, vec![1, 4, 1, 1, 4, 10]);
+        let unfolded: Vec<u64> = day
+            .records
             .iter()
-            .zip(broken_pattern)
-            .map(|(row, pattern)| {
-                let joined_row = row.clone() + &("?".to_string() + row).repeat(4);
-                let join_broken_pattern = pattern.repeat(5);
-                count_combinations(joined_row, join_broken_pattern)
-            })
+            .map(|record| record.unfold(UNFOLD_FACTOR).count_arrangements())
             .collect();
+        assert_eq!(unfolded, vec![1, 16384, 1, 16, 2500, 506250]);
 
-        assert_eq!(count.iter().sum::<usize>(), 850504257483930);
+        for record in day.records.iter() {
+            let row = String::from_utf8(record.springs.clone()).unwrap();
+            assert_eq!(
+                record.count_arrangements(),
+                count_valid_combinations(row, &record.groups)
+            );
+        }
     }
 
     #[test]
-    fn test_is_valid_row() {
-        assert!(is_valid_row("#.#.###", &vec![1, 1, 3]));
-        assert!(!is_valid_row("##..###", &vec![1, 1, 3]));
-
-        assert!(is_valid_row("#....######..#####.", &vec![1, 6, 5]));
+    fn edge_cases() {
+        assert_eq!(count_arrangements(b"", &[]), 1);
+        assert_eq!(count_arrangements(b"???", &[]), 1);
+        assert_eq!(count_arrangements(b"#", &[]), 0);
+        assert_eq!(count_arrangements(b"", &[1]), 0);
+        assert_eq!(count_arrangements(b"###", &[3]), 1);
+        assert_eq!(count_arrangements(b"###", &[2]), 0);
+        assert_eq!(count_arrangements(b"?#?", &[1, 1]), 0);
+        assert_eq!(count_arrangements(b"?????", &[1, 1]), 6);
+
+        let record = Record {
+            springs: b"#.".to_vec(),
+            groups: vec![1],
+        };
+        assert_eq!(record.unfold(3).springs, b"#.?#.?#.");
+        assert_eq!(record.unfold(3).groups, vec![1, 1, 1]);
     }
 }
@@ -1,83 +1,53 @@
-pub fn find_reflect_col(pattern: &Vec<String>, allowed_col_diff: usize) -> Option<usize> {
-    (1..pattern[0].chars().count()).find(|mirror_col| {
-        let take_n = *vec![*mirror_col, pattern[0].len() - (*mirror_col)]
-            .iter()
-            .min()
-            .unwrap();
-        let cols_left = (0..take_n)
-            .map(|icol| {
-                pattern
-                    .iter()
-                    .map(|row| row.chars().nth(mirror_col - icol - 1).unwrap())
-                    .collect()
-            })
-            .collect::<Vec<Vec<char>>>();
-        let cols_right = (0..take_n)
-            .map(|icol| {
-                pattern
-                    .iter()
-                    .map(|row| row.chars().nth(mirror_col + icol).unwrap())
-                    .collect()
-            })
-            .collect::<Vec<Vec<char>>>();
+use crate::{
+    error::ParseError,
+    grid::Grid,
+    solution::{Answer, Solution},
+};
 
-        cols_left
-            .iter()
-            .zip(cols_right.iter())
-            .map(|(left, right)| {
-                left.iter()
-                    .zip(right.iter())
-                    .filter(|(l, r)| l != r)
-                    .count()
-            })
-            .sum::<usize>()
-            == allowed_col_diff
-    })
+const DAY: u8 = 13;
+
+pub fn find_reflect_col(pattern: &Grid<char>, allowed_col_diff: usize) -> Option<usize> {
+    find_reflect_row(&pattern.transpose(), allowed_col_diff)
 }
 
-pub fn find_reflect_row(pattern: &Vec<String>, allowed_row_diff: usize) -> Option<usize> {
-    (1..pattern.iter().count()).find(|irow| {
-        let take_n = *vec![*irow, pattern.len() - (*irow)].iter().min().unwrap();
-        let rows_above = pattern
-            .clone()
-            .into_iter()
-            .skip(irow - take_n)
-            .take(take_n)
-            .rev()
-            .collect::<Vec<String>>();
-        let rows_below = pattern
-            .clone()
-            .into_iter()
-            .skip(*irow)
-            .take(take_n)
-            .collect::<Vec<String>>();
-
-        rows_above
-            .iter()
-            .zip(rows_below.iter())
-            .map(|(above, below)| {
-                above
-                    .chars()
-                    .zip(below.chars())
-                    .filter(|(l, r)| l != r)
-                    .count()
+pub fn find_reflect_row(pattern: &Grid<char>, allowed_row_diff: usize) -> Option<usize> {
+    (1..pattern.height()).find(|irow| {
+        let take_n = *irow.min(&(pattern.height() - irow));
+
+        (0..take_n)
+            .map(|offset| {
+                let above = pattern.row(irow - offset - 1);
+                let below = pattern.row(irow + offset);
+                above.iter().zip(below).filter(|(l, r)| l != r).count()
             })
             .sum::<usize>()
             == allowed_row_diff
     })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::Path;
+pub struct Day13 {
+    patterns: Vec<Grid<char>>,
+}
 
-    #[test]
-    fn day13_part1() {
-        let patterns = aoctk::io::read_text_chunks(Path::new("data/day13/input.txt"))
-            .expect("Invalid input. ");
+impl Solution for Day13 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let mut patterns = vec![];
+        // The line that the current pattern starts on, counting from 0.
+        let mut first_line = 0;
+        for chunk in input.split("\n\n") {
+            let pattern = Grid::parse_chars(DAY, chunk, ".#").map_err(|err| {
+                let line = err.line + first_line;
+                err.on_line(line)
+            })?;
+            first_line += chunk.lines().count() + 1;
+            patterns.push(pattern);
+        }
+        Ok(Day13 { patterns })
+    }
 
-        let res: Vec<usize> = patterns
+    fn part1(&self) -> Answer {
+        let res: Vec<usize> = self
+            .patterns
             .iter()
             .map(|pat| {
                 let col = match find_reflect_col(pat, 0) {
@@ -93,15 +63,12 @@ mod tests {
             })
             .collect();
 
-        assert_eq!(res.iter().sum::<usize>(), 31739);
+        res.iter().sum::<usize>().into()
     }
 
-    #[test]
-    fn day13_part2() {
-        let patterns = aoctk::io::read_text_chunks(Path::new("data/day13/input.txt"))
-            .expect("Invalid input. ");
-
-        let res: Vec<usize> = patterns
+    fn part2(&self) -> Answer {
+        let res: Vec<usize> = self
+            .patterns
             .iter()
             .map(|pat| {
                 let col = match find_reflect_col(pat, 1) {
@@ -113,11 +80,40 @@ mod tests {
                     None => 0,
                 };
 
-                println!("col: {}, row: {}", col, row);
                 col + 100 * row
             })
             .collect();
 
-        assert_eq!(res.iter().sum::<usize>(), 31539);
+        res.iter().sum::<usize>().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day13_part1() {
+        let Some(input) = test_input(13) else {
+            return;
+        };
+
+        assert_eq!(
+            Day13::parse(&input).unwrap().part1(),
+            test_answer(13, Part::One)
+        );
+    }
+
+    #[test]
+    fn day13_part2() {
+        let Some(input) = test_input(13) else {
+            return;
+        };
+
+        assert_eq!(
+            Day13::parse(&input).unwrap().part2(),
+            test_answer(13, Part::Two)
+        );
     }
 }
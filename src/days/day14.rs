@@ -1,3 +1,11 @@
+use crate::{
+    error::ParseError,
+    grid::Grid,
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 14;
+
 pub fn fall(col: Vec<char>) -> Vec<char> {
     let mut result = col.clone();
     let mut nchange = 1;
@@ -14,56 +22,50 @@ pub fn fall(col: Vec<char>) -> Vec<char> {
     result
 }
 
-pub fn do_cycle(grid: &Vec<Vec<char>>) -> Vec<Vec<char>> {
+// Rolls every rock as far north as it will go.
+pub fn tilt_north(grid: &Grid<char>) -> Grid<char> {
+    let columns = grid
+        .transpose()
+        .rows()
+        .map(|col| fall(col.to_vec()))
+        .collect();
+    Grid::from_rows(columns).unwrap().transpose()
+}
+
+pub fn do_cycle(grid: &Grid<char>) -> Grid<char> {
     let mut res = grid.clone();
-    for _ in 0..3 {
-        res = aoctk::grid::transpose(res);
-        res = res
-            .iter()
-            .map(|col| fall(col.clone()))
-            .collect::<Vec<Vec<char>>>();
-        res = res
-            .iter()
-            .map(|row| row.iter().rev().cloned().collect())
-            .collect()
+    // Rotating clockwise after each tilt brings west, then south, then east
+    // round to the north.
+    for _ in 0..4 {
+        res = tilt_north(&res).rotate_cw();
     }
     res
 }
 
-pub fn grid_hash(grid: &Vec<Vec<char>>) -> String {
-    grid.iter()
-        .map(|row| row.iter().collect::<String>())
-        .collect::<Vec<String>>()
-        .join("")
-}
-
-pub fn find_cycle_len(grid: &Vec<Vec<char>>) -> (usize, usize) {
+pub fn find_cycle_len(grid: &Grid<char>) -> (usize, usize) {
     let mut result = grid.clone();
     let mut i = 0;
-    let mut seen_states: Vec<String> = Vec::new();
+    let mut seen_states: Vec<Grid<char>> = Vec::new();
 
     loop {
         let new_grid = do_cycle(&result);
         result = new_grid;
 
-        if seen_states.contains(&grid_hash(&result)) {
+        if seen_states.contains(&result) {
             break;
         }
 
-        seen_states.push(grid_hash(&result));
+        seen_states.push(result.clone());
         i += 1;
     }
 
-    let offset = seen_states
-        .iter()
-        .position(|el| *el == grid_hash(&result))
-        .unwrap();
+    let offset = seen_states.iter().position(|el| *el == result).unwrap();
     let cycle_len = i - offset;
 
     (offset, cycle_len)
 }
 
-pub fn spin_n(grid: &Vec<Vec<char>>, cycles: usize) -> Vec<Vec<char>> {
+pub fn spin_n(grid: &Grid<char>, cycles: usize) -> Grid<char> {
     let mut result = grid.clone();
     for _ in 0..cycles {
         let new_grid = do_cycle(&result);
@@ -72,47 +74,64 @@ pub fn spin_n(grid: &Vec<Vec<char>>, cycles: usize) -> Vec<Vec<char>> {
     result
 }
 
+pub fn north_load(grid: &Grid<char>) -> usize {
+    grid.rows()
+        .enumerate()
+        .map(|(i, row)| row.iter().filter(|&&c| c == 'O').count() * (grid.height() - i))
+        .sum()
+}
+
+pub struct Day14 {
+    grid: Grid<char>,
+}
+
+impl Solution for Day14 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Day14 {
+            grid: Grid::parse_chars(DAY, input, "O#.")?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        north_load(&tilt_north(&self.grid)).into()
+    }
+
+    fn part2(&self) -> Answer {
+        const TARGET_CYCLES: usize = 1000000000;
+        let (offset, cycle_len) = find_cycle_len(&self.grid);
+        let spun = spin_n(&self.grid, offset + (TARGET_CYCLES - offset) % cycle_len);
+
+        north_load(&spun).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use std::path::Path;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
 
     #[test]
     fn day14_part1() {
-        let grid = aoctk::grid::transpose(
-            aoctk::io::grid_from_file(Path::new("data/day14/input.txt"))
-                .expect("Could not read grid"),
+        let Some(input) = test_input(14) else {
+            return;
+        };
+
+        assert_eq!(
+            Day14::parse(&input).unwrap().part1(),
+            test_answer(14, Part::One)
         );
-        let rolled = grid
-            .iter()
-            .map(|col| fall(col.clone()))
-            .collect::<Vec<Vec<char>>>();
-        let rolled = aoctk::grid::transpose(rolled);
-
-        let result = rolled
-            .iter()
-            .enumerate()
-            .map(|(i, row)| row.iter().filter(|&&c| c == 'O').count() * (rolled.iter().count() - i))
-            .sum::<usize>();
-
-        assert_eq!(result, 112048);
     }
 
     #[test]
     fn day14_part2() {
-        const TARGET_CYCLES: usize = 1000000000;
-        let grid = aoctk::io::grid_from_file(Path::new("data/day14/input.txt"))
-            .expect("Could not read grid");
-        let (offset, cycle_len) = find_cycle_len(&grid);
-        let spun = spin_n(&grid, offset + (TARGET_CYCLES - offset) % cycle_len);
-
-        let result = spun
-            .iter()
-            .enumerate()
-            .map(|(i, row)| row.iter().filter(|&&c| c == 'O').count() * (spun.iter().count() - i))
-            .sum::<usize>();
-
-        assert_eq!(result, 105606);
+        let Some(input) = test_input(14) else {
+            return;
+        };
+
+        assert_eq!(
+            Day14::parse(&input).unwrap().part2(),
+            test_answer(14, Part::Two)
+        );
     }
 }
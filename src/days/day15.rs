@@ -1,3 +1,11 @@
+use crate::{
+    error::{parse_lines, parse_token, ParseError},
+    solution::{Answer, Solution},
+};
+use std::collections::HashMap;
+
+const DAY: u8 = 15;
+
 pub fn hashfunc(seg: &str) -> i32 {
     seg.chars().fold(0, |accum: i32, val| {
         let mut tmp = accum + (val as u8) as i32;
@@ -7,24 +15,49 @@ pub fn hashfunc(seg: &str) -> i32 {
     })
 }
 
-#[cfg(test)]
-mod tests {
+/// Checks that a step is either `label-` or `label=N`.
+fn check_step(line: &str, step: &str) -> Result<(), ParseError> {
+    let label = match step.split_once("=") {
+        Some((label, focus)) => {
+            parse_token::<usize>(DAY, line, focus)?;
+            label
+        }
+        None => step.strip_suffix("-").ok_or_else(|| {
+            ParseError::new(DAY, "expected `<label>-` or `<label>=<focus>`").at(line, step)
+        })?,
+    };
+    if label.is_empty() || !label.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(ParseError::new(DAY, "expected a label of letters").at(line, label));
+    }
+    Ok(())
+}
 
-    const INPUT: &str = include_str!("../../data/day15/input.txt");
-    use super::*;
-    use std::collections::HashMap;
+pub struct Day15 {
+    sequence: String,
+}
 
-    #[test]
-    fn day15_part1() {
-        let output = INPUT.split(",").map(hashfunc).collect::<Vec<i32>>();
-        assert_eq!(output.iter().sum::<i32>(), 511498);
+impl Solution for Day15 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        parse_lines(input, |line| {
+            line.split(",")
+                .filter(|step| !step.is_empty())
+                .try_for_each(|step| check_step(line, step))
+        })?;
+        Ok(Day15 {
+            // Newlines are ignored when parsing the initialisation sequence.
+            sequence: input.replace('\n', ""),
+        })
     }
 
-    #[test]
-    fn day15_part2() {
+    fn part1(&self) -> Answer {
+        let output = self.sequence.split(",").map(hashfunc).collect::<Vec<i32>>();
+        output.iter().sum::<i32>().into()
+    }
+
+    fn part2(&self) -> Answer {
         let mut boxes: HashMap<usize, Vec<(String, usize)>> = HashMap::new();
 
-        for seg in INPUT.split(",") {
+        for seg in self.sequence.split(",") {
             // First find the box that we are modifying, using the hash function.
             let label = match seg.split_once("=") {
                 Some((label, _)) => label.to_string(),
@@ -68,6 +101,37 @@ mod tests {
             })
             .sum();
 
-        assert_eq!(tot, 284674);
+        tot.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day15_part1() {
+        let Some(input) = test_input(15) else {
+            return;
+        };
+
+        assert_eq!(
+            Day15::parse(&input).unwrap().part1(),
+            test_answer(15, Part::One)
+        );
+    }
+
+    #[test]
+    fn day15_part2() {
+        let Some(input) = test_input(15) else {
+            return;
+        };
+
+        assert_eq!(
+            Day15::parse(&input).unwrap().part2(),
+            test_answer(15, Part::Two)
+        );
     }
 }
@@ -1,7 +1,41 @@
+use crate::{
+    error::ParseError,
+    grid::{Coord, Grid},
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    Empty,
+    /// `/`
+    ForwardMirror,
+    /// `\`
+    BackMirror,
+    /// `|`
+    VerticalSplitter,
+    /// `-`
+    HorizontalSplitter,
+}
+
+impl Tile {
+    pub fn from_char(c: char) -> Option<Tile> {
+        match c {
+            '.' => Some(Tile::Empty),
+            '/' => Some(Tile::ForwardMirror),
+            '\\' => Some(Tile::BackMirror),
+            '|' => Some(Tile::VerticalSplitter),
+            '-' => Some(Tile::HorizontalSplitter),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Beam {
-    pub pos: (i32, i32),
-    pub dir: (i32, i32),
+    pub pos: Coord,
+    pub dir: Coord,
 }
 
 impl Beam {
@@ -13,35 +47,32 @@ impl Beam {
     }
 }
 
-fn get_tile_visits(grid: &Vec<Vec<char>>, in_beam: Beam) -> Vec<Vec<usize>> {
-    let mut energy = aoctk::grid::new_grid(grid.len(), grid[0].len());
-    let mut splits: Vec<(i32, i32)> = vec![];
+fn get_tile_visits(grid: &Grid<Tile>, in_beam: Beam) -> Grid<usize> {
+    let mut energy = Grid::new(grid.height(), grid.width(), 0);
+    let mut splits: Vec<Coord> = vec![];
     let mut beam_stack: Vec<Beam> = vec![in_beam.clone()];
 
     while let Some(mut beam) = beam_stack.pop() {
-        while beam.pos.0 >= 0
-            && beam.pos.0 < grid.len() as i32
-            && beam.pos.1 >= 0
-            && beam.pos.1 < grid[0].len() as i32
-        {
+        while let Some(&cell) = grid.get(beam.pos) {
             // First, energise the grid at this point.
-            energy[beam.pos.0 as usize][beam.pos.1 as usize] += 1;
+            energy[beam.pos] += 1;
 
-            let cell = grid[beam.pos.0 as usize][beam.pos.1 as usize];
             match cell {
-                '.' => {}
-                '\\' => {
+                Tile::Empty => {}
+                Tile::BackMirror => {
                     let tmp = beam.dir.1;
                     beam.dir.1 = beam.dir.0;
                     beam.dir.0 = tmp;
                 }
-                '/' => {
+                Tile::ForwardMirror => {
                     let tmp = -beam.dir.1;
                     beam.dir.1 = -beam.dir.0;
                     beam.dir.0 = tmp;
                 }
-                cell if cell == '|' || cell == '-' => {
-                    if (cell == '|' && beam.dir.1 != 0) || (cell == '-' && beam.dir.0 != 0) {
+                Tile::VerticalSplitter | Tile::HorizontalSplitter => {
+                    if (cell == Tile::VerticalSplitter && beam.dir.1 != 0)
+                        || (cell == Tile::HorizontalSplitter && beam.dir.0 != 0)
+                    {
                         // Check that we have not already sampled this branch.
                         if splits.contains(&beam.pos) {
                             break;
@@ -60,7 +91,6 @@ fn get_tile_visits(grid: &Vec<Vec<char>>, in_beam: Beam) -> Vec<Vec<usize>> {
                         beam = new_beam;
                     }
                 }
-                _ => panic!("Invalid cell. "),
             }
 
             // Now move the beam in the new direction.
@@ -72,43 +102,38 @@ fn get_tile_visits(grid: &Vec<Vec<char>>, in_beam: Beam) -> Vec<Vec<usize>> {
     energy
 }
 
-fn count_energised_tiles(energy: &Vec<Vec<usize>>) -> usize {
-    energy
-        .iter()
-        .map(|r| r.iter().filter(|val| **val > 0).count())
-        .sum()
+fn count_energised_tiles(energy: &Grid<usize>) -> usize {
+    energy.iter().filter(|(_, val)| **val > 0).count()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::Path;
+pub struct Day16 {
+    grid: Grid<Tile>,
+}
 
-    #[test]
-    fn day16_part1() {
-        let grid = aoctk::io::grid_from_file(Path::new("data/day16/input.txt"))
-            .expect("Unable to find grid file.");
-        let beam = Beam::new();
-        let energy = get_tile_visits(&grid, beam);
+impl Solution for Day16 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Day16 {
+            grid: Grid::parse_with(DAY, input, "one of `.|-/\\`", Tile::from_char)?,
+        })
+    }
 
-        let energised_tiles = count_energised_tiles(&energy);
+    fn part1(&self) -> Answer {
+        let beam = Beam::new();
+        let energy = get_tile_visits(&self.grid, beam);
 
-        assert_eq!(energised_tiles, 7562);
+        count_energised_tiles(&energy).into()
     }
 
-    #[test]
-    fn day16_part2() {
-        let grid = aoctk::io::grid_from_file(Path::new("data/day16/input.txt"))
-            .expect("Unable to find grid file.");
-        let ni = grid.len();
-        let nj = grid[0].len();
+    fn part2(&self) -> Answer {
+        let ni = self.grid.height() as i64;
+        let nj = self.grid.width() as i64;
 
         let north_max = (0..nj)
             .map(|j| {
                 let mut beam = Beam::new();
-                beam.pos = (0, j as i32);
-                beam.dir = (-1, 0);
-                let energy = get_tile_visits(&grid, beam);
+                beam.pos = (0, j);
+                beam.dir = (1, 0);
+                let energy = get_tile_visits(&self.grid, beam);
                 count_energised_tiles(&energy)
             })
             .max()
@@ -117,9 +142,9 @@ mod tests {
         let east_max = (0..ni)
             .map(|i| {
                 let mut beam = Beam::new();
-                beam.pos = (i as i32, nj as i32 - 1);
+                beam.pos = (i, nj - 1);
                 beam.dir = (0, -1);
-                let energy = get_tile_visits(&grid, beam);
+                let energy = get_tile_visits(&self.grid, beam);
                 count_energised_tiles(&energy)
             })
             .max()
@@ -128,31 +153,65 @@ mod tests {
         let south_max = (0..nj)
             .map(|j| {
                 let mut beam = Beam::new();
-                beam.pos = (ni as i32 - 1, j as i32);
-                beam.dir = (0, -1);
-                let energy = get_tile_visits(&grid, beam);
+                beam.pos = (ni - 1, j);
+                beam.dir = (-1, 0);
+                let energy = get_tile_visits(&self.grid, beam);
                 count_energised_tiles(&energy)
             })
             .max()
             .unwrap();
 
         let west_max = (0..ni)
-            .map(|j| {
+            .map(|i| {
                 let mut beam = Beam::new();
-                beam.pos = (ni as i32 - 1, 0);
+                beam.pos = (i, 0);
                 beam.dir = (0, 1);
-                let energy = get_tile_visits(&grid, beam);
+                let energy = get_tile_visits(&self.grid, beam);
                 count_energised_tiles(&energy)
             })
             .max()
             .unwrap();
 
+        [north_max, east_max, south_max, west_max]
+            .into_iter()
+            .max()
+            .unwrap()
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day16_part1() {
+        let Some(input) = test_input(16) else {
+            return;
+        };
+
         assert_eq!(
-            *vec![north_max, east_max, south_max, west_max]
-                .iter()
-                .max()
-                .unwrap(),
-            7793
+            Day16::parse(&input).unwrap().part1(),
+            test_answer(16, Part::One)
         );
     }
+
+    #[test]
+    fn day16_part2() {
+        let Some(input) = test_input(16) else {
+            return;
+        };
+
+        assert_eq!(
+            Day16::parse(&input).unwrap().part2(),
+            test_answer(16, Part::Two)
+        );
+    }
+
+    #[test]
+    fn unexpected_tile() {
+        let err = Day16::parse(".|.\n.x.").err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "x"));
+    }
 }
@@ -1,185 +1,153 @@
-#[derive(Copy, Clone, Eq, PartialEq, Debug)]
+use crate::{
+    error::ParseError,
+    grid::{Coord, Grid, ORTHOGONAL},
+    search::{astar, Successors},
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 17;
+
+#[derive(Copy, Clone, Eq, PartialEq, Hash, Debug)]
 pub struct State {
-    pub hl: usize,
-    pub pos: (i32, i32),
-    pub dir: (i32, i32),
+    pub pos: Coord,
+    pub dir: Coord,
     pub dir_steps: usize,
 }
 
-impl State {
-    pub fn summary(&self) -> ((i32, i32), (i32, i32), usize) {
-        (self.pos, self.dir, self.dir_steps)
-    }
-}
-
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.hl.cmp(&self.hl)
-    }
+/// A crucible that has to move at least `min_run` and at most `max_run`
+/// blocks in a straight line before it can turn.
+pub struct Crucible<'a> {
+    pub grid: &'a Grid<usize>,
+    pub min_run: usize,
+    pub max_run: usize,
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
-#[cfg(test)]
-mod tests {
-
-    use super::*;
-    use std::{
-        collections::{BinaryHeap, HashSet},
-        path::Path,
-    };
-
-    #[test]
-    fn day17_part1() {
-        let grid = aoctk::io::grid_from_file(Path::new("data/day17/input.txt"))
-            .expect("Unable to find grid file.");
-        let grid: Vec<Vec<usize>> = grid
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|val| val.to_string().parse::<usize>().unwrap())
-                    .collect()
-            })
-            .collect();
-
-        let target = (grid.len() as i32 - 1, grid[0].len() as i32 - 1);
-        let mut states = BinaryHeap::from(vec![State {
-            hl: 0,
-            pos: (0, 0),
-            dir: (0, 0),
-            dir_steps: 0,
-        }]);
-        let mut seen_states = HashSet::new();
-        let mut hl = 0;
-
-        while let Some(curr) = states.pop() {
-            hl = curr.hl;
+impl Successors for Crucible<'_> {
+    type State = State;
 
-            if curr.pos == target {
-                break;
-            }
-
-            if seen_states.contains(&curr.summary()) {
-                continue;
-            }
-
-            seen_states.insert(curr.summary());
-
-            if curr.dir != (0, 0) && curr.dir_steps < 3 {
-                let mut next = curr.clone();
-                next.pos.0 = curr.pos.0 + curr.dir.0;
-                next.pos.1 = curr.pos.1 + curr.dir.1;
-                next.dir_steps += 1;
-                if next.pos.0 >= 0
-                    && next.pos.1 >= 0
-                    && next.pos.0 < grid.len() as i32
-                    && next.pos.1 < grid[0].len() as i32
-                {
-                    next.hl += grid[next.pos.0 as usize][next.pos.1 as usize];
-                    states.push(next);
-                }
-            }
+    fn successors(&self, curr: &State) -> Vec<(State, u64)> {
+        let mut moves = vec![];
+        if curr.dir != (0, 0) && curr.dir_steps < self.max_run {
+            moves.push((curr.dir, curr.dir_steps + 1));
+        }
 
-            for dir in vec![(1, 0), (0, 1), (-1, 0), (0, -1)] {
+        if curr.dir_steps >= self.min_run || curr.dir == (0, 0) {
+            for dir in ORTHOGONAL {
                 if dir != curr.dir && dir != (-curr.dir.0, -curr.dir.1) {
-                    let mut next = curr.clone();
-                    next.dir = dir;
-                    next.pos.0 = next.pos.0 + next.dir.0;
-                    next.pos.1 = next.pos.1 + next.dir.1;
-                    next.dir_steps = 1;
-
-                    if next.pos.0 >= 0
-                        && next.pos.1 >= 0
-                        && next.pos.0 < grid.len() as i32
-                        && next.pos.1 < grid[0].len() as i32
-                    {
-                        next.hl += grid[next.pos.0 as usize][next.pos.1 as usize];
-                        states.push(next);
-                    }
+                    moves.push((dir, 1));
                 }
             }
         }
 
-        assert_eq!(hl, 1195);
-    }
-
-    #[test]
-    fn day17_part2() {
-        let grid = aoctk::io::grid_from_file(Path::new("data/day17/input.txt"))
-            .expect("Unable to find grid file.");
-        let grid: Vec<Vec<usize>> = grid
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|val| val.to_string().parse::<usize>().unwrap())
-                    .collect()
+        moves
+            .into_iter()
+            .filter_map(|(dir, dir_steps)| {
+                let pos = (curr.pos.0 + dir.0, curr.pos.1 + dir.1);
+                let loss = *self.grid.get(pos)?;
+                Some((
+                    State {
+                        pos,
+                        dir,
+                        dir_steps,
+                    },
+                    loss as u64,
+                ))
             })
-            .collect();
+            .collect()
+    }
+}
 
-        let target = (grid.len() as i32 - 1, grid[0].len() as i32 - 1);
-        let mut states = BinaryHeap::from(vec![State {
-            hl: 0,
+impl Crucible<'_> {
+    /// The least heat that can be lost getting from the top left to the
+    /// bottom right of the grid.
+    pub fn least_heat_loss(&self) -> Option<u64> {
+        let target = (self.grid.height() as i64 - 1, self.grid.width() as i64 - 1);
+        let start = State {
             pos: (0, 0),
             dir: (0, 0),
             dir_steps: 0,
-        }]);
-        let mut seen_states = HashSet::new();
-        let mut hl = 0;
+        };
+        // Every block costs at least this much to enter, so it can be used to
+        // underestimate the rest of the route.
+        let min_loss = self.grid.iter().map(|(_, loss)| *loss).min().unwrap_or(0) as u64;
+        let to_target = |state: &State| {
+            (state.pos.0.abs_diff(target.0) + state.pos.1.abs_diff(target.1)) * min_loss
+        };
+
+        // The crucible needs to have moved at least `min_run` blocks before it
+        // can stop at the end.
+        astar(
+            self,
+            start,
+            |state| state.pos == target && state.dir_steps >= self.min_run,
+            to_target,
+        )
+        .map(|route| route.cost)
+    }
+}
 
-        while let Some(curr) = states.pop() {
-            hl = curr.hl;
+pub struct Day17 {
+    grid: Grid<usize>,
+}
 
-            if curr.pos == target {
-                break;
-            }
+impl Solution for Day17 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let grid = Grid::parse_with(DAY, input, "a digit", |c| {
+            c.to_digit(10).map(|loss| loss as usize)
+        })?;
+        Ok(Day17 { grid })
+    }
 
-            if seen_states.contains(&curr.summary()) {
-                continue;
-            }
+    fn part1(&self) -> Answer {
+        let crucible = Crucible {
+            grid: &self.grid,
+            min_run: 1,
+            max_run: 3,
+        };
+        crucible
+            .least_heat_loss()
+            .map_or(Answer::Unsolved, Answer::from)
+    }
 
-            seen_states.insert(curr.summary());
-
-            if curr.dir != (0, 0) && curr.dir_steps < 10 {
-                let mut next = curr.clone();
-                next.pos.0 = curr.pos.0 + curr.dir.0;
-                next.pos.1 = curr.pos.1 + curr.dir.1;
-                next.dir_steps += 1;
-                if next.pos.0 >= 0
-                    && next.pos.1 >= 0
-                    && next.pos.0 < grid.len() as i32
-                    && next.pos.1 < grid[0].len() as i32
-                {
-                    next.hl += grid[next.pos.0 as usize][next.pos.1 as usize];
-                    states.push(next);
-                }
-            }
+    fn part2(&self) -> Answer {
+        let crucible = Crucible {
+            grid: &self.grid,
+            min_run: 4,
+            max_run: 10,
+        };
+        crucible
+            .least_heat_loss()
+            .map_or(Answer::Unsolved, Answer::from)
+    }
+}
 
-            if curr.dir_steps >= 4 || curr.dir == (0, 0) {
-                for dir in vec![(1, 0), (0, 1), (-1, 0), (0, -1)] {
-                    if dir != curr.dir && dir != (-curr.dir.0, -curr.dir.1) {
-                        let mut next = curr.clone();
-                        next.dir = dir;
-                        next.pos.0 = next.pos.0 + next.dir.0;
-                        next.pos.1 = next.pos.1 + next.dir.1;
-                        next.dir_steps = 1;
-
-                        if next.pos.0 >= 0
-                            && next.pos.1 >= 0
-                            && next.pos.0 < grid.len() as i32
-                            && next.pos.1 < grid[0].len() as i32
-                        {
-                            next.hl += grid[next.pos.0 as usize][next.pos.1 as usize];
-                            states.push(next);
-                        }
-                    }
-                }
-            }
-        }
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
 
-        assert_eq!(hl, 1347);
+    #[test]
+    fn day17_part1() {
+        let Some(input) = test_input(17) else {
+            return;
+        };
+
+        assert_eq!(
+            Day17::parse(&input).unwrap().part1(),
+            test_answer(17, Part::One)
+        );
+    }
+
+    #[test]
+    fn day17_part2() {
+        let Some(input) = test_input(17) else {
+            return;
+        };
+
+        assert_eq!(
+            Day17::parse(&input).unwrap().part2(),
+            test_answer(17, Part::Two)
+        );
     }
 }
@@ -1,104 +1,161 @@
-#[cfg(test)]
-pub fn parse_instruction(instruction: &str) -> (i64, char) {
-    let steps =
-        i64::from_str_radix(&instruction.chars().skip(2).take(5).collect::<String>(), 16).unwrap();
-    let dirchar = match instruction.chars().rev().skip(1).next().unwrap() {
-        '0' => 'R',
-        '1' => 'D',
-        '2' => 'L',
-        '3' => 'U',
-        _ => panic!("Invalid instruction code. "),
+use crate::{
+    error::{parse_lines, parse_token, ParseError},
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 18;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// The step taken by digging `length` metres in this direction.
+    fn step(self, length: i64) -> (i64, i64) {
+        match self {
+            Direction::Up => (-length, 0),
+            Direction::Right => (0, length),
+            Direction::Down => (length, 0),
+            Direction::Left => (0, -length),
+        }
+    }
+}
+
+/// Decodes the colour code, e.g. `(#70c710)`, into the real length and
+/// direction of the step.
+pub fn parse_instruction(instruction: &str) -> Result<(i64, Direction), ParseError> {
+    let code = instruction
+        .strip_prefix("(#")
+        .and_then(|code| code.strip_suffix(")"))
+        .filter(|code| code.len() == 6 && code.is_ascii())
+        .ok_or_else(|| ParseError::new(DAY, "expected a colour code like `(#70c710)`"))?;
+    let steps = i64::from_str_radix(&code[..5], 16)
+        .map_err(|_| ParseError::new(DAY, "expected a hex distance"))?;
+    let direction = match &code[5..] {
+        "0" => Direction::Right,
+        "1" => Direction::Down,
+        "2" => Direction::Left,
+        "3" => Direction::Up,
+        _ => return Err(ParseError::new(DAY, "invalid instruction code")),
     };
 
-    (steps, dirchar)
+    Ok((steps, direction))
 }
 
+/// Finds the volume of the lagoon dug out by following the dig plan.
+pub fn lagoon_volume(plan: &[(i64, Direction)]) -> i64 {
+    let mut curr_coord = (0, 0);
+    let mut verts = vec![(0, 0)];
+    let mut boundary_len = 0;
+    let mut other_verts = plan
+        .iter()
+        .map(|&(length, direction)| {
+            boundary_len += length;
+            let dir = direction.step(length);
+
+            curr_coord.0 += dir.0;
+            curr_coord.1 += dir.1;
+            curr_coord
+        })
+        .collect();
+    verts.append(&mut other_verts);
+
+    // Now shoelace the vertices to find the interior area.
+    let area = verts
+        .windows(2)
+        .map(|points| points[0].0 * points[1].1 - points[1].0 * points[0].1)
+        .sum::<i64>()
+        / 2;
+
+    // Fix the missing parts of the area using Pick's Theorem.
+    let i = area.abs() - boundary_len / 2 + 1;
+
+    boundary_len + i
+}
+
+pub struct Day18 {
+    plan: Vec<(i64, Direction)>,
+    colour_plan: Vec<(i64, Direction)>,
+}
+
+impl Solution for Day18 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let (plan, colour_plan) = parse_lines(input, |line| {
+            let segs: Vec<&str> = line.split_whitespace().collect();
+            if segs.len() != 3 {
+                return Err(
+                    ParseError::new(DAY, "expected `<direction> <length> <colour>`").at(line, line),
+                );
+            }
+            let direction = match segs[0] {
+                "U" => Direction::Up,
+                "R" => Direction::Right,
+                "D" => Direction::Down,
+                "L" => Direction::Left,
+                _ => return Err(ParseError::new(DAY, "unknown direction").at(line, segs[0])),
+            };
+            let length: i64 = parse_token(DAY, line, segs[1])?;
+            let instruction = parse_instruction(segs[2]).map_err(|err| err.at(line, segs[2]))?;
+
+            Ok(((length, direction), instruction))
+        })?
+        .into_iter()
+        .unzip();
+
+        Ok(Day18 { plan, colour_plan })
+    }
+
+    fn part1(&self) -> Answer {
+        lagoon_volume(&self.plan).into()
+    }
+
+    fn part2(&self) -> Answer {
+        lagoon_volume(&self.colour_plan).into()
+    }
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
-
-    const INPUT: &str = include_str!("../../data/day18/input.txt");
+    use crate::{answers::test_answer, input::test_input, solution::Part};
 
     #[test]
     fn day18_part1() {
-        let mut curr_coord = (0, 0);
-        let mut verts = vec![(0, 0)];
-        let mut boundary_len = 0;
-        let mut other_verts = INPUT
-            .lines()
-            .map(|line| {
-                let segs: Vec<&str> = line.split_whitespace().collect();
-                let length: i64 = segs[1].parse().unwrap();
-                boundary_len += length;
-                let dir = match segs[0] {
-                    "U" => (-length, 0),
-                    "R" => (0, length),
-                    "D" => (length, 0),
-                    "L" => (0, -length),
-                    _ => panic!("Unexpected direction. "),
-                };
-
-                curr_coord.0 += dir.0;
-                curr_coord.1 += dir.1;
-                curr_coord
-            })
-            .collect();
-        verts.append(&mut other_verts);
-
-        // Now shoelace the vertices to find the interior area.
-        let area = verts
-            .windows(2)
-            .map(|points| {
-                println!("{:?}", points);
-                points[0].0 * points[1].1 - points[1].0 * points[0].1
-            })
-            .sum::<i64>()
-            / 2;
-
-        // Fix the missing parts of the area using Pick's Theorem.
-        let i = area.abs() - boundary_len / 2 + 1;
-
-        assert_eq!(boundary_len + i, 46334);
+        let Some(input) = test_input(18) else {
+            return;
+        };
+
+        assert_eq!(
+            Day18::parse(&input).unwrap().part1(),
+            test_answer(18, Part::One)
+        );
     }
 
     #[test]
     fn day18_part2() {
-        let mut curr_coord = (0, 0);
-        let mut verts = vec![(0, 0)];
-        let mut boundary_len = 0;
-        let mut other_verts = INPUT
-            .lines()
-            .map(|line| {
-                let instruction: &str = line.split_whitespace().last().unwrap();
-                let (length, dirchar) = parse_instruction(instruction);
-                boundary_len += length;
-                let dir = match dirchar {
-                    'U' => (-length, 0),
-                    'R' => (0, length),
-                    'D' => (length, 0),
-                    'L' => (0, -length),
-                    _ => panic!("Unexpected direction. "),
-                };
-
-                curr_coord.0 += dir.0;
-                curr_coord.1 += dir.1;
-                curr_coord
-            })
-            .collect();
-        verts.append(&mut other_verts);
-
-        // Now shoelace the vertices to find the interior area.
-        let area = verts
-            .windows(2)
-            .map(|points| {
-                println!("{:?}", points);
-                points[0].0 * points[1].1 - points[1].0 * points[0].1
-            })
-            .sum::<i64>()
-            / 2;
-
-        // Fix the missing parts of the area using Pick's Theorem.
-        let i = area.abs() - boundary_len / 2 + 1;
-
-        assert_eq!(boundary_len + i, 102000662718092);
+        let Some(input) = test_input(18) else {
+            return;
+        };
+
+        assert_eq!(
+            Day18::parse(&input).unwrap().part2(),
+            test_answer(18, Part::Two)
+        );
+    }
+
+    #[test]
+    fn dig_plan_errors() {
+        let err = Day18::parse("R 6 (#70c710)\nX 5 (#0dc571)").err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 1, "X"));
+
+        let err = Day18::parse("R 6 (#70c714)").err().unwrap();
+        assert_eq!(
+            (err.line, err.column, err.text.as_str()),
+            (1, 5, "(#70c714)")
+        );
     }
 }
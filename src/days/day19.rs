@@ -1,4 +1,15 @@
+use crate::{
+    error::{parse_lines, parse_token, ParseError},
+    solution::{Answer, Solution},
+};
 use regex::Regex;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    str::FromStr,
+};
+
+const DAY: u8 = 19;
 
 #[derive(Debug, Clone)]
 pub struct Part {
@@ -8,15 +19,39 @@ pub struct Part {
     s: i32,
 }
 
+impl FromStr for Part {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Part, ParseError> {
+        let pat = Regex::new("^\\{x=([0-9]+),m=([0-9]+),a=([0-9]+),s=([0-9]+)\\}$").unwrap();
+        let caps = pat.captures(input).ok_or_else(|| {
+            ParseError::new(DAY, "expected a part like `{x=1,m=2,a=3,s=4}`").at(input, input)
+        })?;
+        Ok(Part {
+            x: parse_token(DAY, input, caps.get(1).unwrap().as_str())?,
+            m: parse_token(DAY, input, caps.get(2).unwrap().as_str())?,
+            a: parse_token(DAY, input, caps.get(3).unwrap().as_str())?,
+            s: parse_token(DAY, input, caps.get(4).unwrap().as_str())?,
+        })
+    }
+}
+
+/// One of the four categories that each part is rated in.
+#[derive(Debug, Clone, Copy)]
+pub enum Category {
+    X,
+    M,
+    A,
+    S,
+}
+
 impl Part {
-    pub fn from_str(input: &str) -> Part {
-        let pat = Regex::new("\\{x=([0-9]+),m=([0-9]+),a=([0-9]+),s=([0-9]+)\\}").unwrap();
-        let caps = pat.captures(input).unwrap();
-        Part { 
-            x: caps[1].parse().unwrap(), 
-            m: caps[2].parse().unwrap(), 
-            a: caps[3].parse().unwrap(), 
-            s: caps[4].parse().unwrap(),
+    fn rating(&self, category: Category) -> i32 {
+        match category {
+            Category::X => self.x,
+            Category::M => self.m,
+            Category::A => self.a,
+            Category::S => self.s,
         }
     }
 }
@@ -26,101 +61,265 @@ pub enum WorkflowOutcome {
     Accept,
     Reject,
     Workflow(String),
-    Rule(String),
 }
 
-impl WorkflowOutcome {
-    pub fn from_str(input: &str) -> WorkflowOutcome {
+impl FromStr for WorkflowOutcome {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<WorkflowOutcome, ParseError> {
         match input {
-            "A" => Self::Accept,
-            "R" => Self::Reject,
-            _ => if input.contains(":") {
-                Self::Rule(input.to_string())
-            } else {
-                Self::Workflow(input.to_string())
+            "A" => Ok(Self::Accept),
+            "R" => Ok(Self::Reject),
+            _ if !input.is_empty() && input.chars().all(|c| c.is_ascii_lowercase()) => {
+                Ok(Self::Workflow(input.to_string()))
             },
+            _ => Err(ParseError::new(DAY, "expected `A`, `R` or a workflow name").at(input, input)),
         }
     }
 }
 
-pub fn eval_rule(part: &Part, rule: String) -> WorkflowOutcome {
-    let eval_pat = regex::Regex::new("([x|m|a|s])([<|>])([0-9]+)").unwrap();
+/// A rule like `x<10:abc`, which sends a part to `target` if its rating
+/// compares to `value` with the given ordering.
+#[derive(Debug, Clone)]
+pub struct Rule {
+    category: Category,
+    ordering: Ordering,
+    value: i32,
+    target: WorkflowOutcome,
+}
+
+#[derive(Debug, Clone)]
+pub struct Workflow {
+    rules: Vec<Rule>,
+    /// Where a part goes if none of the rules match it.
+    fallback: WorkflowOutcome,
+}
+
+/// Parses where a rule sends a part, checking that any workflow it names is
+/// one of `names`.
+fn parse_target(
+    line: &str,
+    target: &str,
+    names: &HashSet<&str>,
+) -> Result<WorkflowOutcome, ParseError> {
+    let outcome = target.parse().map_err(|err: ParseError| err.at(line, target))?;
+    match &outcome {
+        WorkflowOutcome::Workflow(name) if !names.contains(name.as_str()) => {
+            Err(ParseError::new(DAY, "there is no workflow with this name").at(line, target))
+        },
+        _ => Ok(outcome),
+    }
+}
+
+fn parse_rule(line: &str, rule: &str, names: &HashSet<&str>) -> Result<Rule, ParseError> {
+    let invalid = || ParseError::new(DAY, "expected a rule like `x<10:abc`").at(line, rule);
+    let (condition, target) = rule.split_once(":").ok_or_else(invalid)?;
 
-    // Get the command from the string. 
-    let (expr, options) = rule.split_once(":").unwrap();
-    let caps = eval_pat.captures(&rule).unwrap();
-    let val = match &caps[1] {
-        "x" => part.x,
-        "m" => part.m,
-        "a" => part.a,
-        "s" => part.s,
-        _ => panic!("Invalid part parameter. "),
+    let mut chars = condition.chars();
+    let category = match chars.next() {
+        Some('x') => Category::X,
+        Some('m') => Category::M,
+        Some('a') => Category::A,
+        Some('s') => Category::S,
+        _ => return Err(invalid()),
     };
-    let cmp: i32 = caps[3].parse().unwrap();
-    let result = match &caps[2] {
-        "<" => val < cmp,
-        ">" => val > cmp,
-        _ => panic!("Unexpected operator. "),
+    let ordering = match chars.next() {
+        Some('<') => Ordering::Less,
+        Some('>') => Ordering::Greater,
+        _ => return Err(invalid()),
     };
 
-    let (acc, rej) = options.split_once(",").unwrap();
-    let outcome = if result {
-        WorkflowOutcome::from_str(acc)
-    } else {
-        WorkflowOutcome::from_str(rej)
-    };
+    Ok(Rule {
+        category,
+        ordering,
+        value: parse_token(DAY, line, chars.as_str())?,
+        target: parse_target(line, target, names)?,
+    })
+}
+
+/// Parses a workflow line like `name{x<10:abc,m>5:A,R}` into its name and
+/// rules. Every workflow that the rules send parts to must be in `names`.
+fn parse_workflow(line: &str, names: &HashSet<&str>) -> Result<(String, Workflow), ParseError> {
+    let (name, rules) = line
+        .split_once("{")
+        .filter(|(name, rules)| !name.is_empty() && rules.ends_with("}"))
+        .ok_or_else(|| {
+            ParseError::new(DAY, "expected a workflow like `name{...}`").at(line, line)
+        })?;
+    let rules = &rules[..rules.len() - 1];
 
-    match outcome {
-        WorkflowOutcome::Accept | WorkflowOutcome::Reject | WorkflowOutcome::Workflow(_)=> outcome,
-        WorkflowOutcome::Rule(rule) => eval_rule(part, rule)
+    let mut conditions: Vec<&str> = rules.split(",").collect();
+    let fallback = conditions.pop().unwrap();
+    if fallback.is_empty() || fallback.contains(":") {
+        return Err(ParseError::new(DAY, "a workflow must end with a fallback").at(line, fallback));
     }
-}
 
-#[cfg(test)]
-mod tests {
+    let workflow = Workflow {
+        rules: conditions
+            .into_iter()
+            .map(|rule| parse_rule(line, rule, names))
+            .collect::<Result<_, _>>()?,
+        fallback: parse_target(line, fallback, names)?,
+    };
+    Ok((name.to_string(), workflow))
+}
 
-    use std::collections::HashMap;
+/// Finds where the workflow sends the part, which is the target of the first
+/// rule that matches it.
+pub fn eval_workflow<'a>(part: &Part, workflow: &'a Workflow) -> &'a WorkflowOutcome {
+    workflow
+        .rules
+        .iter()
+        .find(|rule| part.rating(rule.category).cmp(&rule.value) == rule.ordering)
+        .map_or(&workflow.fallback, |rule| &rule.target)
+}
 
-    use super::*;
+/// Follows the rules from the workflow `name` depth first, looking for one
+/// that sends parts back to a workflow that is already on the path, which
+/// would send them round forever. Gives the workflow with that rule, and the
+/// index of the rule, where the fallback comes after all the others.
+fn find_cycle<'a>(
+    workflows: &'a HashMap<String, Workflow>,
+    name: &'a str,
+    on_path: &mut HashSet<&'a str>,
+    finished: &mut HashSet<&'a str>,
+) -> Option<(&'a str, usize)> {
+    on_path.insert(name);
+    let workflow = &workflows[name];
+    let targets = workflow.rules.iter().map(|rule| &rule.target).chain([&workflow.fallback]);
+    for (k, target) in targets.enumerate() {
+        let WorkflowOutcome::Workflow(next) = target else {
+            continue;
+        };
+        if on_path.contains(next.as_str()) {
+            return Some((name, k));
+        }
+        if !finished.contains(next.as_str()) {
+            if let Some(cycle) = find_cycle(workflows, next, on_path, finished) {
+                return Some(cycle);
+            }
+        }
+    }
+    on_path.remove(name);
+    finished.insert(name);
+    None
+}
 
-    const INPUT: &str = include_str!("../../data/day19/input.txt");
+pub struct Day19 {
+    workflows: HashMap<String, Workflow>,
+    parts: Vec<Part>,
+}
 
-    #[test]
-    fn day19_part1() {
-        let workflows: HashMap<String, String> = INPUT
+impl Solution for Day19 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let (workflow_lines, part_lines) = input.split_once("\n\n").ok_or_else(|| {
+            ParseError::new(DAY, "expected a blank line between the workflows and parts")
+        })?;
+        // Find every workflow's name and line first, so that the rules can be
+        // checked against them.
+        let lines: HashMap<&str, (usize, &str)> = workflow_lines
             .lines()
-            .take_while(|line| *line != "")
-            .map(|line| {
-                let (name, rules) = line.split_once("{").unwrap();
-                (name.to_string(), rules.replace("}", "").to_string())
-            })
+            .enumerate()
+            .filter_map(|(i, line)| Some((line.split_once("{")?.0, (i + 1, line))))
             .collect();
+        let names: HashSet<&str> = lines.keys().copied().collect();
+        let workflows: HashMap<String, Workflow> =
+            parse_lines(workflow_lines, |line| parse_workflow(line, &names))?
+                .into_iter()
+                .collect();
+        if !workflows.contains_key("in") {
+            return Err(ParseError::new(DAY, "missing the `in` workflow"));
+        }
+        if let Some((name, k)) =
+            find_cycle(&workflows, "in", &mut HashSet::new(), &mut HashSet::new())
+        {
+            let (i, line) = lines[name];
+            // The rules are split by commas, between the name and the braces.
+            let rule = line.split(['{', ',', '}']).nth(k + 1).unwrap_or(line);
+            return Err(ParseError::new(DAY, "this rule sends parts round in a cycle")
+                .at(line, rule)
+                .on_line(i));
+        }
 
-        let parts: Vec<Part> = INPUT
-            .lines()
-            .skip_while(|line| *line != "")
-            .skip(1)
-            .map(|line| Part::from_str(line))
-            .collect();
-        
+        // The parts start after the workflows and the blank line.
+        let offset = workflow_lines.lines().count() + 1;
+        let parts: Vec<Part> = parse_lines(part_lines, |line| line.parse::<Part>())
+            .map_err(|err| {
+                let line = err.line + offset;
+                err.on_line(line)
+            })?;
+
+        Ok(Day19 { workflows, parts })
+    }
+
+    fn part1(&self) -> Answer {
         let mut tot = 0;
-        for part in parts {
-            let mut res = WorkflowOutcome::Workflow(String::from("in"));
+        for part in self.parts.iter() {
+            let mut workflow = &self.workflows["in"];
             loop {
-                match res {
+                match eval_workflow(part, workflow) {
                     WorkflowOutcome::Accept => {
                         tot += part.x + part.m + part.a + part.s;
                         break;
                     },
                     WorkflowOutcome::Reject => break,
-                    WorkflowOutcome::Rule(_) => panic!("Should not make it to here. "),
-                    WorkflowOutcome::Workflow(wf) => {
-                        res = eval_rule(&part, workflows[&wf].clone());
-                    }
+                    // Parsing checked that every workflow named in a rule exists,
+                    // and that they don't go round in a cycle.
+                    WorkflowOutcome::Workflow(wf) => workflow = &self.workflows[wf],
                 }
             }
         }
 
-        assert_eq!(tot, 330820);
+        tot.into()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    fn parse_error(input: &str) -> ParseError {
+        Day19::parse(input).err().unwrap()
+    }
+
+    #[test]
+    fn day19_part1() {
+        let Some(input) = test_input(19) else {
+            return;
+        };
+
+        assert_eq!(Day19::parse(&input).unwrap().part1(), test_answer(19, Part::One));
+    }
+
+    #[test]
+    fn workflow_errors() {
+        let err = parse_error("in{x<10:abc,R}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 9, "abc"));
+
+        let err = parse_error("in{x<10:A,abc}\nab{R}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 11, "abc"));
+
+        let err = parse_error("in{x<10:A,ab}\nab{q>5:R,A}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 4, "q>5:R"));
+
+        let err = parse_error("in{x=10:A,R}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 4, "x=10:A"));
+
+        let err = parse_error("in{x<ten:A,R}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 6, "ten"));
+
+        let err = parse_error("in{x<10:Ab,R}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!((err.line, err.column, err.text.as_str()), (1, 9, "Ab"));
+
+        let err = parse_error("in{x<10:ab,R}\nab{in}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 4, "in"));
+
+        let err = parse_error("in{x<10:ab,bc}\nab{s>5:bc,A}\nbc{m<3:R,ab}\n\n{x=1,m=2,a=3,s=4}");
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 10, "ab"));
+
+        // Two rules leading to the same workflow is not a cycle.
+        assert!(Day19::parse("in{x<10:ab,ab}\nab{R}\n\n{x=1,m=2,a=3,s=4}").is_ok());
+    }
+}
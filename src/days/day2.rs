@@ -1,8 +1,70 @@
-#[cfg(test)]
-mod tests {
+use crate::{
+    error::{parse_lines, parse_token, ParseError},
+    solution::{Answer, Solution},
+};
+
+const DAY: u8 = 2;
+
+/// The cubes that are shown in a single turn of a game.
+#[derive(Debug, Clone, Default)]
+pub struct Turn {
+    pub red: u32,
+    pub green: u32,
+    pub blue: u32,
+}
 
-    use aoctk;
-    use std::path::Path;
+#[derive(Debug, Clone)]
+pub struct Game {
+    pub id: u32,
+    pub turns: Vec<Turn>,
+}
+
+impl Game {
+    pub fn parse(line: &str) -> Result<Game, ParseError> {
+        let (header, turns) = line
+            .split_once(": ")
+            .ok_or_else(|| ParseError::new(DAY, "expected `Game N: ...`").at(line, line))?;
+        let id_str = header
+            .strip_prefix("Game ")
+            .ok_or_else(|| ParseError::new(DAY, "expected `Game N`").at(line, header))?;
+        let id = parse_token(DAY, line, id_str)?;
+
+        let turns = turns
+            .split("; ")
+            .map(|turn| {
+                let mut shown = Turn::default();
+                for draw in turn.split(", ") {
+                    let (count, colour) = draw.split_once(" ").ok_or_else(|| {
+                        ParseError::new(DAY, "expected `<count> <colour>`").at(line, draw)
+                    })?;
+                    let count = parse_token(DAY, line, count)?;
+                    match colour {
+                        "red" => shown.red = count,
+                        "green" => shown.green = count,
+                        "blue" => shown.blue = count,
+                        _ => {
+                            return Err(ParseError::new(DAY, "unknown cube colour").at(line, colour))
+                        }
+                    }
+                }
+                Ok(shown)
+            })
+            .collect::<Result<Vec<Turn>, ParseError>>()?;
+
+        Ok(Game { id, turns })
+    }
+}
+
+pub struct Day2 {
+    games: Vec<Game>,
+}
+
+impl Solution for Day2 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Day2 {
+            games: parse_lines(input, Game::parse)?,
+        })
+    }
 
     /// A simple solution to part 1.
     /// We need to iterate through the counts of each drawn colour in each turn in
@@ -10,133 +72,96 @@ mod tests {
     /// time. If the number that is shown in a given turn is higher than the maximum
     /// (because they are returns, so we don't have to consider between turns), then
     /// the game is not possible.
-    #[test]
-    fn day2_part1() {
+    fn part1(&self) -> Answer {
         // Define the maximum number of possible each colour of cube.
         const MAX_RED_CUBES: u32 = 12;
         const MAX_GREEN_CUBES: u32 = 13;
         const MAX_BLUE_CUBES: u32 = 14;
 
-        // First read in the data from the file.
-        let lines = aoctk::io::read_string_col(Path::new("data/day2/data.txt")).unwrap();
-
-        let possible_games_id_total: u32 = lines
+        let possible_games_id_total: u32 = self
+            .games
             .iter()
-            .map(|line| {
-                // First get the index of the game.
-                let segments = line.split(": ").collect::<Vec<&str>>();
-                let game_id = segments[0].replace("Game ", "").parse::<u32>().unwrap();
-
-                // Setup our counters for this game.
-                let mut red_cubes = 0;
-                let mut green_cubes = 0;
-                let mut blue_cubes = 0;
-
-                // Now iterate through the turns in the game.
-                let turns = segments[1].split("; ").collect::<Vec<&str>>();
-
-                let game_possible = turns
-                    .iter()
-                    .map(|turn| {
-                        let drawn_colours = turn.split(", ").collect::<Vec<&str>>();
-
-                        for draws in drawn_colours {
-                            let draw_segments = draws.split(" ").collect::<Vec<&str>>();
-                            let cube_count = draw_segments[0].parse::<u32>().unwrap();
-                            let cube_colour = draw_segments[1];
-
-                            // Match up the colour and set our count for the currentoly
-                            // shown number of cubes to that value.
-                            match cube_colour {
-                                "red" => red_cubes = cube_count,
-                                "green" => green_cubes = cube_count,
-                                "blue" => blue_cubes = cube_count,
-                                _ => panic!("Unknown cube colour!"),
-                            }
-                        }
-
-                        if red_cubes <= MAX_RED_CUBES
-                            && green_cubes <= MAX_GREEN_CUBES
-                            && blue_cubes <= MAX_BLUE_CUBES
-                        {
-                            true
-                        } else {
-                            false
-                        }
-                    })
-                    .all(|x| x == true);
+            .map(|game| {
+                let game_possible = game.turns.iter().all(|turn| {
+                    turn.red <= MAX_RED_CUBES
+                        && turn.green <= MAX_GREEN_CUBES
+                        && turn.blue <= MAX_BLUE_CUBES
+                });
 
                 if game_possible {
-                    game_id
+                    game.id
                 } else {
                     0
                 }
             })
             .sum();
 
-        assert_eq!(possible_games_id_total, 2348);
+        possible_games_id_total.into()
     }
 
     /// A super basic solutoin to part 2.
     /// We effectively want to know the minimum number of cubes of each colour that
     /// we need to show at any given time.
-    #[test]
-    fn day2_part2() {
-        // First read in the data from the file.
-        let lines = aoctk::io::read_string_col(Path::new("data/day2/data.txt")).unwrap();
-
-        let total_powers: u32 = lines
+    fn part2(&self) -> Answer {
+        let total_powers: u32 = self
+            .games
             .iter()
-            .map(|line| {
-                // First get the index of the game.
-                let segments = line.split(": ").collect::<Vec<&str>>();
-
-                // Setup our counters for this game.
-                let mut red_cubes = 0;
-                let mut green_cubes = 0;
-                let mut blue_cubes = 0;
-
-                // Now iterate through the turns in the game.
-                let turns = segments[1].split("; ").collect::<Vec<&str>>();
-
-                for turn in turns {
-                    let drawn_colours = turn.split(", ").collect::<Vec<&str>>();
-
-                    for draws in drawn_colours {
-                        let draw_segments = draws.split(" ").collect::<Vec<&str>>();
-                        let cube_count = draw_segments[0].parse::<u32>().unwrap();
-                        let cube_colour = draw_segments[1];
-
-                        // A simple case of checking to see if the number of cubes
-                        // shown in the current turn exceeds our current maximum.
-                        // This means we will only estimate the maximum number of
-                        // each colour to make the game possible, hence enabling
-                        // us to calculate the total power.
-                        match cube_colour {
-                            "red" => {
-                                if cube_count > red_cubes {
-                                    red_cubes = cube_count
-                                }
-                            }
-                            "green" => {
-                                if cube_count > green_cubes {
-                                    green_cubes = cube_count
-                                }
-                            }
-                            "blue" => {
-                                if cube_count > blue_cubes {
-                                    blue_cubes = cube_count
-                                }
-                            }
-                            _ => panic!("Unknown cube colour!"),
-                        }
-                    }
-                }
+            .map(|game| {
+                // A simple case of checking to see if the number of cubes
+                // shown in each turn exceeds our current maximum.
+                // This means we will only estimate the maximum number of
+                // each colour to make the game possible, hence enabling
+                // us to calculate the total power.
+                let red_cubes = game.turns.iter().map(|turn| turn.red).max().unwrap_or(0);
+                let green_cubes = game.turns.iter().map(|turn| turn.green).max().unwrap_or(0);
+                let blue_cubes = game.turns.iter().map(|turn| turn.blue).max().unwrap_or(0);
 
                 red_cubes * green_cubes * blue_cubes
             })
             .sum();
 
-        assert_eq!(total_powers, 76008);
+        total_powers.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day2_part1() {
+        // First read in the data from the file.
+        let Some(input) = test_input(2) else {
+            return;
+        };
+
+        assert_eq!(
+            Day2::parse(&input).unwrap().part1(),
+            test_answer(2, Part::One)
+        );
+    }
+
+    #[test]
+    fn day2_part2() {
+        // First read in the data from the file.
+        let Some(input) = test_input(2) else {
+            return;
+        };
+
+        assert_eq!(
+            Day2::parse(&input).unwrap().part2(),
+            test_answer(2, Part::Two)
+        );
+    }
+
+    #[test]
+    fn day2_parse_error() {
+        let err = Day2::parse("Game 1: 3 blue\nGame 2: 4 purple")
+            .err()
+            .unwrap();
+
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 11, "purple"));
     }
 }
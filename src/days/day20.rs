@@ -1,10 +1,26 @@
 use std::{
-    collections::{VecDeque, HashMap},
+    collections::{VecDeque, HashMap, HashSet},
     cell::RefCell, borrow::BorrowMut,
 };
 
+use crate::{
+    dot::Digraph,
+    error::ParseError,
+    solution::{Answer, Solution},
+};
+use num::integer::lcm;
 use regex::Regex;
 
+const DAY: u8 = 20;
+
+/// The module that part 2 waits for a low pulse to reach.
+const END_MODULE: &str = "rx";
+
+/// The most times that the button is pressed in part 2 while waiting for each
+/// input of the module feeding `rx` to send a high pulse. The cycles in the
+/// puzzle input are all under 4096 presses long.
+const MAX_PRESSES: i64 = 100_000;
+
 // In this problem, we use bools for the pulses: low = false, high = true. 
 
 #[derive(Debug, Clone)]
@@ -35,17 +51,6 @@ impl ModuleHandler {
         }
     }
 
-    pub fn val(&mut self) -> Option<bool> {
-        match self {
-            Self::Untyped(val) => { 
-                let tmp = val.clone(); 
-                *val = None; 
-                tmp
-            },
-            _ => panic!("Unexpected variant for value. "),
-        }
-    }
-
     pub fn add_src(&mut self, src: String) {
         match self {
             ModuleHandler::Conjunction(state) => {
@@ -62,24 +67,28 @@ pub struct Module {
     pub destinations: Vec<String>,
 }
 
-pub fn parse_modules(input: &str) -> HashMap<String, RefCell<Module>> {
-    let line_regex = Regex::new("([&|%]*)([a-z]+) -> ([a-z, ]+)").unwrap();
+pub fn parse_modules(input: &str) -> Result<HashMap<String, RefCell<Module>>, ParseError> {
+    let line_regex = Regex::new("^([^a-z]?)([a-z]+) -> ([a-z, ]+)$").unwrap();
     let mut modules = HashMap::new();
 
-    for line in input.lines() {
-        let caps = line_regex.captures(line).unwrap();
+    for (i, line) in input.lines().enumerate() {
+        let caps = line_regex.captures(line).ok_or_else(|| {
+            ParseError::new(DAY, "expected a module like `%a -> b, c`")
+                .on_line(i + 1)
+                .at(line, line)
+        })?;
         let key = caps[2].to_string();
         let destinations: Vec<String> = caps[3].split(", ").map(str::to_string).collect();
 
         let handler = match &caps[1] {
             "%" => ModuleHandler::FlipFlop(false),
             "&" => ModuleHandler::Conjunction(HashMap::new()),
-            _ => {
-                if key == "broadcaster" {
-                    ModuleHandler::Broadcaster
-                } else {
-                    ModuleHandler::Untyped(None)
-                }
+            "" if key == "broadcaster" => ModuleHandler::Broadcaster,
+            "" => ModuleHandler::Untyped(None),
+            prefix => {
+                return Err(ParseError::new(DAY, "unknown module type, expected `%` or `&`")
+                    .on_line(i + 1)
+                    .at(line, prefix))
             }
         };
 
@@ -108,23 +117,79 @@ pub fn parse_modules(input: &str) -> HashMap<String, RefCell<Module>> {
         }
     }
 
-    modules
+    Ok(modules)
 }
 
-#[cfg(test)]
-mod tests {
+/// Every module that the pulses from `start` can reach, including `start`.
+fn reachable_from(modules: &HashMap<String, RefCell<Module>>, start: &str) -> HashSet<String> {
+    let mut reached = HashSet::from([start.to_string()]);
+    let mut queue = VecDeque::from([start.to_string()]);
+    while let Some(name) = queue.pop_front() {
+        let Some(m) = modules.get(&name) else {
+            continue;
+        };
+        for dest in m.borrow().destinations.iter() {
+            if reached.insert(dest.clone()) {
+                queue.push_back(dest.clone());
+            }
+        }
+    }
+    reached
+}
 
-    use std::borrow::Borrow;
+/// Writes the modules as a Graphviz graph. Each module is labelled with its
+/// type, as in the input, and drawn in its own shape. The `rx` module that
+/// part 2 waits for is drawn as a double circle. If `highlight_from` names a
+/// module, everything that its pulses can reach is coloured in.
+pub fn to_dot(modules: &HashMap<String, RefCell<Module>>, highlight_from: Option<&str>) -> String {
+    let mut names: Vec<&String> = modules.keys().collect();
+    names.sort();
 
-    use super::*;
-    use num::integer::lcm;
+    let mut graph = Digraph::new("day20");
+    for name in names.iter() {
+        let (prefix, shape) = match modules[*name].borrow().handler {
+            ModuleHandler::FlipFlop(_) => ("%", "box"),
+            ModuleHandler::Conjunction(_) => ("&", "diamond"),
+            ModuleHandler::Broadcaster => ("", "doubleoctagon"),
+            ModuleHandler::Untyped(_) if *name == END_MODULE => ("", "doublecircle"),
+            ModuleHandler::Untyped(_) => ("", "plaintext"),
+        };
+        graph.node(name, &[("label", &format!("{}{}", prefix, name)), ("shape", shape)]);
+    }
+    for name in names.iter() {
+        for dest in modules[*name].borrow().destinations.iter() {
+            graph.edge(name, dest, &[]);
+        }
+    }
+    if let Some(start) = highlight_from {
+        graph.highlight_reachable(start);
+    }
+    graph.to_string()
+}
 
-    const INPUT: &str = include_str!("../../data/day20/input.txt");
+pub struct Day20 {
+    modules: HashMap<String, RefCell<Module>>,
+}
+
+impl Day20 {
+    pub fn to_dot(&self, highlight_from: Option<&str>) -> String {
+        to_dot(&self.modules, highlight_from)
+    }
+}
+
+impl Solution for Day20 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let modules = parse_modules(input)?;
+        if !modules.contains_key("broadcaster") {
+            return Err(ParseError::new(DAY, "missing the `broadcaster` module"));
+        }
+        Ok(Day20 { modules })
+    }
+
+    fn part1(&self) -> Answer {
+        // Each part needs to start from the modules in their initial states.
+        let modules = self.modules.clone();
 
-    #[test]
-    fn day20_part1() {
-        let modules = parse_modules(INPUT);
-        
         let npulse = 1000;
         let mut low_pulse: i64 = 0;
         let mut high_pulse: i64 = 0;
@@ -135,7 +200,10 @@ mod tests {
             low_pulse += 1;
 
             while let Some((from, to, input)) = queue.pop_front() {
-                let mut m = modules.get(&to).unwrap().borrow_mut();
+                let Some(m) = modules.get(&to) else {
+                    continue;
+                };
+                let mut m = m.borrow_mut();
                 if let Some(output) = m.handler.handle(input, from) {
                     for dest in m.destinations.iter() {
                         if output {
@@ -149,35 +217,61 @@ mod tests {
             }
         }
 
-        assert_eq!(low_pulse * high_pulse, 929810733);
+        (low_pulse * high_pulse).into()
     }
 
-    #[test]
-    fn day20_part2() {
-        let modules = parse_modules(INPUT);
+    fn part2(&self) -> Answer {
+        let modules = self.modules.clone();
 
         // I've studied my input, and the module that feeds into `rx` is `lg`.
-        // So I want to keep track of all modules that feed into that.
-        let (output_mod, _) = modules.clone().into_iter().find(|(_, m)| m.clone().borrow().destinations.contains(&"rx".to_string())).unwrap();
-        let output_mod = output_mod.clone().to_string();
+        // So I want to keep track of all modules that feed into that. Without
+        // a single conjunction feeding `rx`, like in the examples, the cycles
+        // can't be used to find the answer.
+        let Some((output_mod, feeder)) = modules
+            .iter()
+            .find(|(_, m)| m.borrow().destinations.iter().any(|dest| dest == END_MODULE))
+        else {
+            return Answer::Unsolved;
+        };
+        let ninputs = match &feeder.borrow().handler {
+            ModuleHandler::Conjunction(state) => {
+                // An input that no pulse can reach never sends a high pulse.
+                let reached = reachable_from(&modules, "broadcaster");
+                if !state.keys().all(|input| reached.contains(input)) {
+                    return Answer::Unsolved;
+                }
+                state.len()
+            },
+            _ => return Answer::Unsolved,
+        };
+        let output_mod = output_mod.clone();
         let mut cycle_lengths: HashMap<String, i64> = HashMap::new();
         
         let mut queue: VecDeque<(String, String, bool)> = VecDeque::new();
         let mut icycle = 0;
 
-        while cycle_lengths.len() < 4 {
+        while cycle_lengths.len() < ninputs {
+            // Some inputs might never send a high pulse, even if they can be
+            // reached.
+            if icycle == MAX_PRESSES {
+                return Answer::Unsolved;
+            }
+
             // Inject the first pulse into the broadcast module. 
             queue.push_back(("".to_string(), "broadcaster".to_string(), false));
             icycle += 1;
 
             while let Some((from, to, input)) = queue.pop_front() {
-                let mut m = modules.get(&to).unwrap().borrow_mut();
-                if let Some(output) = m.handler.handle(input, from.clone()) {
+                let Some(m) = modules.get(&to) else {
+                    continue;
+                };
+                let mut m = m.borrow_mut();
+                if let Some(output) = m.handler.handle(input, from) {
                     for dest in m.destinations.iter() {
                         queue.push_back((to.clone(), dest.clone(), output));
 
-                        if dest == &output_mod && output == true {
-                            cycle_lengths.insert(from.clone(), icycle);
+                        if dest == &output_mod && output {
+                            cycle_lengths.entry(to.clone()).or_insert(icycle);
                         }
                     }
                 }
@@ -185,6 +279,82 @@ mod tests {
         }
 
         let min_presses = cycle_lengths.iter().fold(1, |accum, (_, cyc)| lcm(accum, *cyc));
-        assert_eq!(min_presses, 231657829136023);
+        min_presses.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day20_part1() {
+        let Some(input) = test_input(20) else {
+            return;
+        };
+
+        assert_eq!(Day20::parse(&input).unwrap().part1(), test_answer(20, Part::One));
+    }
+
+    #[test]
+    fn day20_part2() {
+        let Some(input) = test_input(20) else {
+            return;
+        };
+
+        assert_eq!(Day20::parse(&input).unwrap().part2(), test_answer(20, Part::Two));
+    }
+
+    #[test]
+    fn unknown_module_type() {
+        let err = Day20::parse("broadcaster -> a\n%a -> b\n$b -> a").err().unwrap();
+        assert_eq!((err.line, err.column, err.text.as_str()), (3, 1, "$"));
+    }
+
+    #[test]
+    fn feeders_of_rx() {
+        // Neither example has an `rx` module to watch.
+        for example in [
+            include_str!("../../samples/day20/example.txt"),
+            include_str!("../../samples/day20/example2.txt"),
+        ] {
+            assert_eq!(Day20::parse(example).unwrap().part2(), Answer::Unsolved);
+        }
+
+        // `ia` sends a high pulse every 2 presses, and `ic` every 4.
+        let input = "broadcaster -> a, b\n%a -> ia\n&ia -> lg\n\
+                     %b -> c\n%c -> ic\n&ic -> lg\n&lg -> rx";
+        assert_eq!(Day20::parse(input).unwrap().part2(), Answer::from(4));
+
+        // `x` is never sent a pulse, so it never sends one to `lg`.
+        let input = "broadcaster -> a\n%a -> lg\n&x -> lg\n&lg -> rx";
+        assert_eq!(Day20::parse(input).unwrap().part2(), Answer::Unsolved);
+
+        // `y` is sent a pulse, but it is always low, so it stays high.
+        let input = "broadcaster -> x\n&x -> y\n&y -> lg\n&lg -> rx";
+        assert_eq!(Day20::parse(input).unwrap().part2(), Answer::Unsolved);
+
+        // Only a conjunction tells us when all of its inputs were high.
+        let input = "broadcaster -> a\n%a -> rx";
+        assert_eq!(Day20::parse(input).unwrap().part2(), Answer::Unsolved);
+    }
+
+    #[test]
+    fn modules_to_dot() {
+        let day = Day20::parse(include_str!("../../samples/day20/example2.txt")).unwrap();
+        let dot = day.to_dot(Some("b"));
+        assert!(dot.contains("\"broadcaster\" [label=\"broadcaster\", shape=\"doubleoctagon\"];"));
+        assert!(dot.contains("\"a\" [label=\"%a\", shape=\"box\"];"));
+        assert!(dot.contains("\"con\" [label=\"&con\", shape=\"diamond\", color=\"red\"];"));
+        assert!(dot.contains("\"output\" [label=\"output\", shape=\"plaintext\", color=\"red\"];"));
+        assert!(dot.contains("\"a\" -> \"inv\";"));
+        assert!(dot.contains("\"b\" -> \"con\" [color=\"red\"];"));
+
+        let day = Day20::parse("broadcaster -> a\n%a -> rx, b").unwrap();
+        let dot = day.to_dot(None);
+        assert!(dot.contains("\"rx\" [label=\"rx\", shape=\"doublecircle\"];"));
+        assert!(dot.contains("\"b\" [label=\"b\", shape=\"plaintext\"];"));
     }
 }
\ No newline at end of file
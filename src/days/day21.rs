@@ -1,9 +1,14 @@
+use crate::{
+    error::ParseError,
+    grid::{Coord, Grid},
+    solution::{Answer, Solution},
+};
 use std::collections::{VecDeque, HashSet};
 
-pub fn walk_grid(grid: &Vec<Vec<char>>, start_pos: (i64, i64), target_steps: i64) -> i64 {
-    let ni = grid.len() as i64;
-    let nj = grid[0].len() as i64;
-    let mut step_queue: VecDeque<(i64, (i64, i64))> = VecDeque::new();
+const DAY: u8 = 21;
+
+pub fn walk_grid(grid: &Grid<char>, start_pos: Coord, target_steps: i64) -> i64 {
+    let mut step_queue: VecDeque<(i64, Coord)> = VecDeque::new();
     let mut visited = HashSet::new();
     let mut answers = HashSet::new();
 
@@ -19,16 +24,11 @@ pub fn walk_grid(grid: &Vec<Vec<char>>, start_pos: (i64, i64), target_steps: i64
             continue;
         }
         
-        for dir in vec![(1, 0), (-1, 0), (0, 1), (0, -1)] {
-            let new_pos = (i + dir.0, j + dir.1);
+        for (new_pos, tile) in grid.neighbours4((i, j)) {
             let new_steps = steps - 1;
-            if new_pos.0 >= 0 && new_pos.1 >= 0 && new_pos.0 < ni && new_pos.1 < nj {
-                if grid[new_pos.0 as usize][new_pos.1 as usize] != '#' {
-                    if !visited.contains(&new_pos) {
-                        visited.insert(new_pos);
-                        step_queue.push_back((new_steps, new_pos))
-                    }
-                }
+            if *tile != '#' && !visited.contains(&new_pos) {
+                visited.insert(new_pos);
+                step_queue.push_back((new_steps, new_pos))
             }
         }
     }
@@ -36,53 +36,30 @@ pub fn walk_grid(grid: &Vec<Vec<char>>, start_pos: (i64, i64), target_steps: i64
     answers.iter().count() as i64
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    const INPUT: &str = include_str!("../../data/day21/input.txt");
+pub struct Day21 {
+    garden: Grid<char>,
+    start_pos: Coord,
+}
 
-    #[test]
-    fn day21_part1() {
-        let garden: Vec<Vec<char>> = INPUT
-            .lines()
-            .map(|line| line.chars().collect())
-            .collect();
+impl Solution for Day21 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let garden = Grid::parse_chars(DAY, input, ".#S")?;
 
         // Find the starting position, by searching for S.
-        let mut start_pos = (0, 0);
-        for i in 0..garden.len() {
-            for j in 0..garden[i].len() {
-                if garden[i][j] == 'S' {
-                    start_pos = (i as i64, j as i64);
-                }
-            }
-        }
+        let start_pos = garden
+            .find(&'S')
+            .ok_or_else(|| ParseError::new(DAY, "the garden has no starting tile `S`"))?;
 
-        let poss_plots = walk_grid(&garden, start_pos, 64);
-        
-        assert_eq!(poss_plots, 3746)
+        Ok(Day21 { garden, start_pos })
     }
 
-    #[test]
-    fn day21_part2() {
-        let garden: Vec<Vec<char>> = INPUT
-            .lines()
-            .map(|line| line.chars().collect())
-            .collect();
-
-        // Find the starting position, by searching for S.
-        let mut start_pos = (0, 0);
-        for i in 0..garden.len() {
-            for j in 0..garden[i].len() {
-                if garden[i][j] == 'S' {
-                    start_pos = (i as i64, j as i64);
-                }
-            }
-        }
+    fn part1(&self) -> Answer {
+        walk_grid(&self.garden, self.start_pos, 64).into()
+    }
 
-        let ni = garden.len() as i64;
-        let nj = garden[0].len() as i64;
+    fn part2(&self) -> Answer {
+        let ni = self.garden.height() as i64;
+        let nj = self.garden.width() as i64;
         let target_steps = 26501365;
         let grid_size = ni;
         
@@ -97,31 +74,62 @@ mod tests {
         let grid_width = target_steps / ni - 1;
 
         let odd_grids = (grid_width / 2 * 2 + 1).pow(2);
-        let odd_visits = walk_grid(&garden, start_pos, grid_size * 2 + 1);
+        let odd_visits = walk_grid(&self.garden, self.start_pos, grid_size * 2 + 1);
 
         let even_grids = ((grid_width + 1) / 2 * 2).pow(2);
-        let even_visits = walk_grid(&garden, start_pos, grid_size * 2);
+        let even_visits = walk_grid(&self.garden, self.start_pos, grid_size * 2);
         
-        let top = walk_grid(&garden, (ni - 1, start_pos.1), ni - 1);
-        let right = walk_grid(&garden, (start_pos.0, 0), nj - 1);
-        let bottom = walk_grid(&garden, (0, start_pos.1), ni - 1 );
-        let left = walk_grid(&garden, (start_pos.0, nj - 1), nj - 1);
+        let top = walk_grid(&self.garden, (ni - 1, self.start_pos.1), ni - 1);
+        let right = walk_grid(&self.garden, (self.start_pos.0, 0), nj - 1);
+        let bottom = walk_grid(&self.garden, (0, self.start_pos.1), ni - 1 );
+        let left = walk_grid(&self.garden, (self.start_pos.0, nj - 1), nj - 1);
 
-        let small_tri_tr = walk_grid(&garden, (ni - 1, 0), grid_size / 2 - 1);
-        let small_tri_tl = walk_grid(&garden, (ni - 1, nj - 1), grid_size / 2 - 1);
-        let small_tri_br = walk_grid(&garden, (0, 0), grid_size / 2 - 1);
-        let small_tri_bl = walk_grid(&garden, (0, nj - 1), grid_size / 2 - 1);
+        let small_tri_tr = walk_grid(&self.garden, (ni - 1, 0), grid_size / 2 - 1);
+        let small_tri_tl = walk_grid(&self.garden, (ni - 1, nj - 1), grid_size / 2 - 1);
+        let small_tri_br = walk_grid(&self.garden, (0, 0), grid_size / 2 - 1);
+        let small_tri_bl = walk_grid(&self.garden, (0, nj - 1), grid_size / 2 - 1);
 
-        let large_tri_tr = walk_grid(&garden, (ni - 1, 0), 3 * grid_size / 2 - 1);
-        let large_tri_tl = walk_grid(&garden, (ni - 1, nj - 1), 3 * grid_size / 2 - 1);
-        let large_tri_br = walk_grid(&garden, (0, 0), 3 * grid_size / 2 - 1);
-        let large_tri_bl = walk_grid(&garden, (0, nj - 1), 3 * grid_size / 2 - 1);
+        let large_tri_tr = walk_grid(&self.garden, (ni - 1, 0), 3 * grid_size / 2 - 1);
+        let large_tri_tl = walk_grid(&self.garden, (ni - 1, nj - 1), 3 * grid_size / 2 - 1);
+        let large_tri_br = walk_grid(&self.garden, (0, 0), 3 * grid_size / 2 - 1);
+        let large_tri_bl = walk_grid(&self.garden, (0, nj - 1), 3 * grid_size / 2 - 1);
 
         let tot = odd_grids * odd_visits + even_grids * even_visits
                 + top + right + bottom + left
                 + (grid_width + 1) * (small_tri_bl + small_tri_br + small_tri_tl + small_tri_tr)
                 + (grid_width) * (large_tri_bl + large_tri_br + large_tri_tl + large_tri_tr);
         
-        assert_eq!(tot, 623540829615589);
+        tot.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day21_part1() {
+        let Some(input) = test_input(21) else {
+            return;
+        };
+
+        assert_eq!(Day21::parse(&input).unwrap().part1(), test_answer(21, Part::One))
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn day21_part2() {
+        let Some(input) = test_input(21) else {
+            return;
+        };
+
+        assert_eq!(Day21::parse(&input).unwrap().part2(), test_answer(21, Part::Two));
+    }
+
+    #[test]
+    fn day21_example() {
+        let day = Day21::parse(include_str!("../../samples/day21/example.txt")).unwrap();
+
+        assert_eq!(walk_grid(&day.garden, day.start_pos, 6), 16);
+    }
+}
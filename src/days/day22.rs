@@ -1,26 +1,42 @@
+use crate::{
+    error::{parse_lines, parse_token, ParseError},
+    solution::{Answer, Solution},
+};
 use std::{
     cell::RefCell,
-    collections::VecDeque,
+    collections::{HashSet, hash_map::RandomState, VecDeque},
+    str::FromStr,
 };
 
+const DAY: u8 = 22;
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Brick {
     pub c1: (i64, i64, i64),
     pub c2: (i64, i64, i64),
 }
 
-impl Brick {
-    pub fn parse(input: &str) -> Brick {
-        let (c1, c2) = input.split_once("~").unwrap();
-        let coords = vec![c1, c2].iter()
+impl FromStr for Brick {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Brick, ParseError> {
+        let (c1, c2) = input.split_once("~").ok_or_else(|| {
+            ParseError::new(DAY, "expected a brick like `1,0,1~1,2,1`").at(input, input)
+        })?;
+        let coords = [c1, c2].iter()
             .map(|c| {
-                let coords: Vec<i64> = c.split(",").map(|s| s.parse::<i64>().unwrap()).collect();
-                (coords[0], coords[1], coords[2])
+                let coords: Vec<i64> = c.split(",")
+                    .map(|s| parse_token(DAY, input, s))
+                    .collect::<Result<Vec<i64>, ParseError>>()?;
+                match coords.as_slice() {
+                    [x, y, z] => Ok((*x, *y, *z)),
+                    _ => Err(ParseError::new(DAY, "expected three coordinates").at(input, c)),
+                }
             })
-            .collect::<Vec<(i64, i64, i64)>>();
+            .collect::<Result<Vec<(i64, i64, i64)>, ParseError>>()?;
         let (c1, c2) = (coords[0], coords[1]);
 
-        Brick {c1, c2}
+        Ok(Brick {c1, c2})
     }
 }
 
@@ -87,19 +103,21 @@ pub fn find_supported_bricks(brick: &Brick, bricks: &Vec<RefCell<Brick>>) -> Vec
         .collect()
 }
 
-#[cfg(test)]
-mod tests {
-
-    use std::collections::{HashSet, hash_map::RandomState};
-
-    use super::*;
+pub struct Day22 {
+    bricks: Vec<RefCell<Brick>>,
+}
 
-    const INPUT: &str = include_str!("../../data/day22/input.txt");
+impl Solution for Day22 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let bricks: Vec<RefCell<Brick>> = parse_lines(input, |line| line.parse::<Brick>())?
+            .into_iter()
+            .map(RefCell::new)
+            .collect();
+        Ok(Day22 { bricks })
+    }
 
-    #[test]
-    fn day22_part1() {
-        let bricks: Vec<RefCell<Brick>> = INPUT.lines().map(|line| RefCell::new(Brick::parse(line))).collect();
-        let bricks = fall(bricks);
+    fn part1(&self) -> Answer {
+        let bricks = fall(self.bricks.clone());
 
         // Check the bricks that support
         let (supporting_bricks, supported_bricks): (Vec<Vec<usize>>, Vec<Vec<usize>>) = bricks
@@ -122,13 +140,11 @@ mod tests {
             })
             .count();
 
-        assert_eq!(count, 401);
+        count.into()
     }
 
-    #[test]
-    fn day22_part2() {
-        let bricks: Vec<RefCell<Brick>> = INPUT.lines().map(|line| RefCell::new(Brick::parse(line))).collect();
-        let bricks = fall(bricks);
+    fn part2(&self) -> Answer {
+        let bricks = fall(self.bricks.clone());
 
         // Check the bricks that support
         let (supporting_bricks, supported_bricks): (Vec<Vec<usize>>, Vec<Vec<usize>>) = bricks
@@ -173,7 +189,32 @@ mod tests {
             // Remember that we need to negate the original disintegrated brick from this total. 
             total += falling_bricks.len() as i64 - 1;
         }
-        assert_eq!(total, 63491);
+        total.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day22_part1() {
+        let Some(input) = test_input(22) else {
+            return;
+        };
+
+        assert_eq!(Day22::parse(&input).unwrap().part1(), test_answer(22, Part::One));
+    }
+
+    #[test]
+    fn day22_part2() {
+        let Some(input) = test_input(22) else {
+            return;
+        };
+
+        assert_eq!(Day22::parse(&input).unwrap().part2(), test_answer(22, Part::Two));
     }
 
 }
\ No newline at end of file
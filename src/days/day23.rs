@@ -1,82 +1,80 @@
-use std::collections::{HashMap, HashSet};
-
-pub fn find_nodes(grid: &Vec<Vec<char>>) -> Vec<(usize, usize)> {
-    let mut nodes = Vec::new();
-    for i in 0..grid.len() as i64 {
-        for j in 0..grid[0].len() as i64 {
-            if grid[i as usize][j as usize] == '#' { continue; }
-
-            let mut n_neigh = 0;
-            for neigh in [(i - 1, j), (i, j + 1), (i + 1, j), (i, j - 1)] {
-                if neigh.0 >= 0 && neigh.1 >= 0 && (neigh.0 as usize) < grid.len() && (neigh.1 as usize) < grid[0].len() && grid[neigh.0 as usize][neigh.1 as usize] != '#' {
-                    n_neigh += 1;
-                }
-            }
-
-            if n_neigh > 2 {
-                nodes.push((i as usize, j as usize));
-            }
-        }
-    }
-    nodes
+use crate::{
+    error::ParseError,
+    grid::{Coord, Grid, ORTHOGONAL},
+    solution::{Answer, Solution},
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const DAY: u8 = 23;
+
+pub fn find_nodes(grid: &Grid<char>) -> Vec<Coord> {
+    grid.iter()
+        .filter(|(pos, tile)| {
+            **tile != '#' && grid.neighbours4(*pos).filter(|(_, c)| **c != '#').count() > 2
+        })
+        .map(|(pos, _)| pos)
+        .collect()
 }
 
-#[cfg(test)]
-pub mod tests {
-
-    use super::*;
-    use std::collections::{VecDeque, HashSet, HashMap};
+pub struct Day23 {
+    grid: Grid<char>,
+}
 
-    const INPUT: &str = include_str!("../../data/day23/input.txt");
+impl Day23 {
+    // The gaps in the top and bottom rows.
+    fn start_and_end(&self) -> (Coord, Coord) {
+        let gap = |i: usize| self.grid.row(i).iter().position(|c| *c == '.').unwrap() as i64;
+        let last = self.grid.height() - 1;
+        ((0, gap(0)), (last as i64, gap(last)))
+    }
+}
 
-    #[test]
-    fn day23_part1() {
-        let grid: Vec<Vec<char>> = INPUT
-            .lines()
-            .map(|line| line.chars().collect())
-            .collect();
+impl Solution for Day23 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let grid = Grid::parse_chars(DAY, input, "#.^>v<")?;
+        if grid.height() == 0 {
+            return Err(ParseError::new(DAY, "the map is empty"));
+        }
+        Ok(Day23 { grid })
+    }
 
-        let start = (0_usize, grid[0].iter().position(|c| *c == '.').unwrap());
-        let end = (grid.len() - 1, grid.last().unwrap().iter().position(|c| *c == '.').unwrap());
+    fn part1(&self) -> Answer {
+        let (start, end) = self.start_and_end();
 
         let mut nodes = vec![start, end];
-        nodes.append(&mut find_nodes(&grid));
+        nodes.append(&mut find_nodes(&self.grid));
 
 
-        let avail_dirs = |c: char| -> Vec<(i64, i64)> { 
+        let avail_dirs = |c: char| -> Vec<Coord> { 
             match c {
                 '^' => vec![(-1, 0)],
                 '>' => vec![(0, 1)],
                 'v' => vec![(1, 0)],
                 '<' => vec![(0, -1)],
-                '.' => vec![(-1, 0), (0, 1), (1, 0), (0, -1)],
+                '.' => ORTHOGONAL.to_vec(),
                 _ => panic!("Unexpected char. "),
             }
         };
 
         // Create a structure to hold the graph connections. 
-        let mut graph: HashMap<(usize, usize), HashMap<(usize, usize), u64>> = HashMap::new();
+        let mut graph: HashMap<Coord, HashMap<Coord, u64>> = HashMap::new();
         graph.insert(end, HashMap::new());
 
-        for &(si, sj) in nodes.iter() {
-            let mut stack = VecDeque::from([(0, si, sj)]);
-            let mut seen = HashSet::from([(si, sj)]);
+        for &start_node in nodes.iter() {
+            let mut stack = VecDeque::from([(0, start_node)]);
+            let mut seen = HashSet::from([start_node]);
 
-            while let Some((n, i, j)) = stack.pop_back() {
-                if n != 0 && nodes.contains(&(i, j)) {
-                    if graph.contains_key(&(si, sj)) {
-                        graph.get_mut(&(si, sj)).unwrap().insert((i, j), n);
-                    } else {
-                        graph.insert((si, sj), HashMap::from_iter([((i, j), n as u64)]));
-                    }
+            while let Some((n, pos)) = stack.pop_back() {
+                if n != 0 && nodes.contains(&pos) {
+                    graph.entry(start_node).or_default().insert(pos, n);
                     continue;
                 }
 
-                for (di, dj) in avail_dirs(grid[i][j]) {
-                    let (ni, nj) = (i as i64 + di, j as i64 + dj);
-                    if ni >= 0 && nj >= 0 && (ni as usize) < grid.len() && (nj as usize) < grid[0].len() && grid[ni as usize][nj as usize] != '#' && !seen.contains(&(ni as usize, nj as usize)) {
-                        stack.push_back((n + 1, ni as usize, nj as usize));
-                        seen.insert((ni as usize, nj as usize));
+                let steps = avail_dirs(self.grid[pos]);
+                for (next, tile) in self.grid.neighbours(pos, &steps) {
+                    if *tile != '#' && !seen.contains(&next) {
+                        stack.push_back((n + 1, next));
+                        seen.insert(next);
                     }
                 }
             }
@@ -86,44 +84,32 @@ pub mod tests {
 
         let max_route = dfs(&mut seen, &graph, start, end);
 
-        assert_eq!(max_route, 2130);
+        max_route.into()
     }
 
-    #[test]
-    fn day23_part2() {
-        let grid: Vec<Vec<char>> = INPUT
-            .lines()
-            .map(|line| line.chars().collect())
-            .collect();
-
-        let start = (0_usize, grid[0].iter().position(|c| *c == '.').unwrap());
-        let end = (grid.len() - 1, grid.last().unwrap().iter().position(|c| *c == '.').unwrap());
+    fn part2(&self) -> Answer {
+        let (start, end) = self.start_and_end();
 
         let mut nodes = vec![start, end];
-        nodes.append(&mut find_nodes(&grid));
+        nodes.append(&mut find_nodes(&self.grid));
 
         // Create a structure to hold the graph connections. 
-        let mut graph: HashMap<(usize, usize), HashMap<(usize, usize), u64>> = HashMap::new();
-
-        for &(si, sj) in nodes.iter() {
-            let mut stack = VecDeque::from([(0, si, sj)]);
-            let mut seen = HashSet::from([(si, sj)]);
-
-            while let Some((n, i, j)) = stack.pop_back() {
-                if n != 0 && nodes.contains(&(i, j)) {
-                    if graph.contains_key(&(si, sj)) {
-                        graph.get_mut(&(si, sj)).unwrap().insert((i, j), n);
-                    } else {
-                        graph.insert((si, sj), HashMap::from_iter([((i, j), n as u64)]));
-                    }
+        let mut graph: HashMap<Coord, HashMap<Coord, u64>> = HashMap::new();
+
+        for &start_node in nodes.iter() {
+            let mut stack = VecDeque::from([(0, start_node)]);
+            let mut seen = HashSet::from([start_node]);
+
+            while let Some((n, pos)) = stack.pop_back() {
+                if n != 0 && nodes.contains(&pos) {
+                    graph.entry(start_node).or_default().insert(pos, n);
                     continue;
                 }
 
-                for (di, dj) in [(-1, 0), (0, 1), (1, 0), (0, -1)] {
-                    let (ni, nj) = (i as i64 + di, j as i64 + dj);
-                    if ni >= 0 && nj >= 0 && (ni as usize) < grid.len() && (nj as usize) < grid[0].len() && grid[ni as usize][nj as usize] != '#' && !seen.contains(&(ni as usize, nj as usize)) {
-                        stack.push_back((n + 1, ni as usize, nj as usize));
-                        seen.insert((ni as usize, nj as usize));
+                for (next, tile) in self.grid.neighbours4(pos) {
+                    if *tile != '#' && !seen.contains(&next) {
+                        stack.push_back((n + 1, next));
+                        seen.insert(next);
                     }
                 }
             }
@@ -133,12 +119,17 @@ pub mod tests {
         let mut seen = HashSet::new();
         let max_route = dfs(&mut seen, &graph, start, end);
 
-        assert_eq!(max_route, 6710);
         // 6921 - too high. 
+        max_route.into()
     }
 }
 
-pub fn dfs(seen: &mut HashSet<(usize, usize)>, graph: &HashMap<(usize, usize), HashMap<(usize, usize), u64>>, key: (usize, usize), end: (usize, usize)) -> i64 {
+pub fn dfs(
+    seen: &mut HashSet<Coord>,
+    graph: &HashMap<Coord, HashMap<Coord, u64>>,
+    key: Coord,
+    end: Coord,
+) -> i64 {
     // Only the real endpoint should give a high solution. 
     if key == end {
         return 0;
@@ -159,4 +150,29 @@ pub fn dfs(seen: &mut HashSet<(usize, usize)>, graph: &HashMap<(usize, usize), H
         });
     seen.remove(&key);
     out
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day23_part1() {
+        let Some(input) = test_input(23) else {
+            return;
+        };
+
+        assert_eq!(Day23::parse(&input).unwrap().part1(), test_answer(23, Part::One));
+    }
+
+    #[test]
+    fn day23_part2() {
+        let Some(input) = test_input(23) else {
+            return;
+        };
+
+        assert_eq!(Day23::parse(&input).unwrap().part2(), test_answer(23, Part::Two));
+    }
+}
@@ -1,25 +1,32 @@
+use crate::{
+    error::{parse_lines, parse_token, ParseError},
+    solution::{Answer, Solution},
+};
 use itertools::Itertools;
 use num::traits::Pow;
+
+const DAY: u8 = 24;
+
+/// The range of x and y positions that the paths need to cross within.
+const TEST_AREA: (f64, f64) = (200000000000000_f64, 400000000000000_f64);
+
 type Hailstone = ((f64, f64, f64), (f64, f64, f64));
 
-pub fn parse_input(instr: &str) -> Vec<Hailstone> {
-    instr
-        .lines()
-        .map(|line| {
-            let (pos_str, vel_str) = line.split_once(" @ ").expect("Invalid format input. ");
-            let pos: (f64, f64, f64) = pos_str
-                .split(", ")
-                .map(|s| s.parse().unwrap())
-                .collect_tuple()
-                .expect("Invalid position");
-            let vec: (f64, f64, f64) = vel_str
-                .split(", ")
-                .map(|s| s.parse().unwrap())
+pub fn parse_input(instr: &str) -> Result<Vec<Hailstone>, ParseError> {
+    parse_lines(instr, |line| {
+        let (pos_str, vel_str) = line.split_once("@").ok_or_else(|| {
+            ParseError::new(DAY, "expected `<position> @ <velocity>`").at(line, line)
+        })?;
+        let parse_triple = |s: &str| -> Result<(f64, f64, f64), ParseError> {
+            let values = s.split(",")
+                .map(|v| parse_token(DAY, line, v))
+                .collect::<Result<Vec<f64>, ParseError>>()?;
+            values.into_iter()
                 .collect_tuple()
-                .expect("Invalid velocity");
-            (pos, vec)
-        })
-        .collect()
+                .ok_or_else(|| ParseError::new(DAY, "expected three values").at(line, s))
+        };
+        Ok((parse_triple(pos_str)?, parse_triple(vel_str)?))
+    })
 }
 
 /// Checks for decreasing distance to see if collision is the past.
@@ -30,51 +37,84 @@ pub fn point_in_future_2d(t: &(f64, f64, f64), p: &(f64, f64, f64), v: &(f64, f6
     init_dist >= final_dist
 }
 
+/// Counts the pairs of hailstones whose paths cross inside the test area,
+/// ignoring the z axis.
+pub fn count_intersections_2d(hailstones: &[Hailstone], test_area: (f64, f64)) -> usize {
+    let (testx, testy) = (test_area, test_area);
+
+    let mut tot_intersect = 0;
+    for pair in hailstones.iter().combinations(2) {
+        let (p1, v1) = pair.first().unwrap();
+        let (p2, v2) = pair.last().unwrap();
+
+        // Don't compare the same hailstone. 
+        if (p1, v1) == (p2, v2) {
+            continue;
+        }
+
+        // Check if the lines are parallel. 
+        let a = v1.1 / v1.0;
+        let b = v2.1 / v2.0;
+        if a == b {
+            // The lines are parallel, they will not meet. 
+            continue;
+        }
+
+        // Find their intersection point. 
+        let c = p1.1 - a * p1.0;
+        let d = p2.1 - b * p2.0;
+        let x_intersect = (d - c) / (a - b);
+        let y_intersect = a * x_intersect + c;
+
+        // Check for the intersection in the test area, and in the future for
+        // both hailstones.
+        let intersect = (x_intersect, y_intersect, 0.0);
+        if x_intersect >= testx.0
+            && x_intersect <= testx.1
+            && y_intersect >= testy.0
+            && y_intersect <= testy.1
+            && point_in_future_2d(&intersect, p1, v1)
+            && point_in_future_2d(&intersect, p2, v2)
+        {
+            tot_intersect += 1;
+        }
+    }
+
+    tot_intersect
+}
+
+pub struct Day24 {
+    hailstones: Vec<Hailstone>,
+}
+
+impl Solution for Day24 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Day24 { hailstones: parse_input(input)? })
+    }
+
+    fn part1(&self) -> Answer {
+        count_intersections_2d(&self.hailstones, TEST_AREA).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    const INPUT: &str = include_str!("../../data/day24/input.txt");
+    use crate::{answers::test_answer, input::test_input, solution::Part};
 
     #[test]
     fn day24_part1() {
-        let hailstones: Vec<Hailstone> = parse_input(INPUT);
-        let (testx, testy) = ((200000000000000_f64, 400000000000000_f64), (200000000000000_f64, 400000000000000_f64));
-        //let (testx, testy) = ((7.0, 27.0), (7.0, 27.0));
-
-        let mut tot_intersect = 0;
-        for pair in hailstones.iter().combinations(2) {
-            let (p1, v1) = pair.first().unwrap();
-            let (p2, v2) = pair.last().unwrap();
-
-            // Don't compare the same hailstone. 
-            if (p1, v1) == (p2, v2) {
-                continue;
-            }
-
-            // Check if the lines are parallel. 
-            let a = v1.1 / v1.0;
-            let b = v2.1 / v2.0;
-            if a == b {
-                // The lines are parallel, they will not meet. 
-                continue;
-            }
-
-            // Find their intersection point. 
-            let c = p1.1 - a * p1.0;
-            let d = p2.1 - b * p2.0;
-            let x_intersect = (d - c) / (a - b);
-            let y_intersect = a * x_intersect + c;
-
-            // Check for the intersection in the test area.
-            if x_intersect >= testx.0 && x_intersect <= testx.1 && y_intersect >= testy.0 && y_intersect <= testy.1 {
-                if point_in_future_2d(&(x_intersect, y_intersect, 0.0), p1, v1) && point_in_future_2d(&(x_intersect, y_intersect, 0.0), p2, v2) {
-                    tot_intersect += 1;
-                }
-            }
-        }
+        let Some(input) = test_input(24) else {
+            return;
+        };
 
-        assert_eq!(tot_intersect, 17776);
+        assert_eq!(Day24::parse(&input).unwrap().part1(), test_answer(24, Part::One));
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn day24_example() {
+        let day = Day24::parse(include_str!("../../samples/day24/example.txt")).unwrap();
+
+        assert_eq!(count_intersections_2d(&day.hailstones, (7.0, 27.0)), 2);
+    }
+}
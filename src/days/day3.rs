@@ -1,69 +1,69 @@
+use crate::{
+    error::ParseError,
+    grid::{Coord, Grid},
+    solution::{Answer, Solution},
+};
 use regex::Regex;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+
+const DAY: u8 = 3;
 
 // Returns the
-fn find_numbers_in_grid(grid: &Vec<Vec<char>>) -> HashMap<(usize, usize), u32> {
+fn find_numbers_in_grid(grid: &Grid<char>) -> HashMap<Coord, u32> {
     let number_pattern = Regex::new("[0-9]+").expect("Invalid Regex. ");
 
     // Find runs of one or more numerical characters in each line.
-    grid.iter()
+    grid.rows()
         .enumerate()
         .map(|(i, row)| {
             number_pattern
                 .find_iter(row.iter().collect::<String>().as_str())
                 .map(|m| {
                     (
-                        (i, m.start()),
+                        (i as i64, m.start() as i64),
                         m.as_str().parse::<u32>().expect("Number is not a number. "),
                     )
                 })
-                .collect::<Vec<((usize, usize), u32)>>()
+                .collect::<Vec<(Coord, u32)>>()
         })
         .flatten()
         .collect::<HashMap<_, _>>()
 }
 
-#[cfg(test)]
-mod tests {
+// A number is a part number if any of the tiles around it, other than the
+// number's own digits, is a symbol.
+fn is_part_number(schematic: &Grid<char>, coord: Coord, number_len: i64) -> bool {
+    let in_number =
+        |pos: Coord| pos.0 == coord.0 && (coord.1..coord.1 + number_len).contains(&pos.1);
+    (0..number_len).any(|j_offset| {
+        schematic
+            .neighbours8((coord.0, coord.1 + j_offset))
+            .any(|(pos, c)| !in_number(pos) && *c != '.')
+    })
+}
 
-    use super::find_numbers_in_grid;
-    use std::{collections::HashSet, path::Path};
+pub struct Day3 {
+    schematic: Grid<char>,
+}
 
-    #[test]
-    fn day3_part1() {
-        let schematic = aoctk::io::grid_from_file(Path::new("data/day3/data.txt"))
-            .expect("Unable to read grid from file. ");
-        let map = find_numbers_in_grid(&schematic);
+impl Solution for Day3 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let schematic = Grid::parse_with(DAY, input, "a digit, `.` or a symbol", |c| {
+            c.is_ascii_graphic().then_some(c)
+        })?;
+        Ok(Day3 { schematic })
+    }
+
+    fn part1(&self) -> Answer {
+        let map = find_numbers_in_grid(&self.schematic);
 
         let final_sum: u32 = map
             .iter()
             .map(|(coord, number)| {
                 // Nice suggestion from: https://stackoverflow.com/a/69298721
-                let number_len = number.checked_ilog10().unwrap_or(0) + 1;
-
-                let is_part_number = (0..number_len)
-                    .map(|j_offset| {
-                        let mut neighbours = aoctk::grid::GridNeighbours::get_neighbours_for_coord(
-                            &schematic,
-                            coord.0,
-                            coord.1 + j_offset as usize,
-                        );
-                        // Handle the case where the east / west cases are the other parts of the number.
-                        if j_offset > 0 {
-                            neighbours.w = None;
-                        }
-
-                        if j_offset < number_len - 1 {
-                            neighbours.e = None;
-                        }
-
-                        Into::<Vec<_>>::into(neighbours)
-                            .iter()
-                            .any(|x| x.unwrap_or('.') != '.')
-                    })
-                    .any(|x| x);
-
-                if is_part_number {
+                let number_len = number.checked_ilog10().unwrap_or(0) as i64 + 1;
+
+                if is_part_number(&self.schematic, *coord, number_len) {
                     number.clone()
                 } else {
                     0
@@ -71,62 +71,35 @@ mod tests {
             })
             .sum();
 
-        assert_eq!(final_sum, 527446);
+        final_sum.into()
     }
 
-    #[test]
-    fn day3_part2() {
-        let schematic = aoctk::io::grid_from_file(Path::new("data/day3/data.txt"))
-            .expect("Unable to read grid from file. ");
-        let map = find_numbers_in_grid(&schematic);
-        let mut geargrid =
-            aoctk::grid::new_grid::<Option<u32>>(schematic.len(), schematic[0].len());
+    fn part2(&self) -> Answer {
+        let map = find_numbers_in_grid(&self.schematic);
+        let mut geargrid: Grid<Option<u32>> =
+            Grid::new(self.schematic.height(), self.schematic.width(), None);
 
         for (coord, number) in map.iter() {
             // Nice suggestion from: https://stackoverflow.com/a/69298721
-            let number_len = number.checked_ilog10().unwrap_or(0) + 1;
-
-            let is_part_number = (0..number_len)
-                .map(|j_offset| {
-                    let mut neighbours = aoctk::grid::GridNeighbours::get_neighbours_for_coord(
-                        &schematic,
-                        coord.0,
-                        coord.1 + j_offset as usize,
-                    );
-                    // Handle the case where the east / west cases are the other parts of the number.
-                    if j_offset > 0 {
-                        neighbours.w = None;
-                    }
-
-                    if j_offset < number_len - 1 {
-                        neighbours.e = None;
-                    }
-
-                    Into::<Vec<_>>::into(neighbours)
-                        .iter()
-                        .any(|x| x.unwrap_or('.') != '.')
-                })
-                .any(|x| x);
+            let number_len = number.checked_ilog10().unwrap_or(0) as i64 + 1;
 
-            if is_part_number {
+            if is_part_number(&self.schematic, *coord, number_len) {
                 for j_offset in 0..number_len {
-                    geargrid[coord.0][coord.1 + j_offset as usize] = Some(*number);
+                    geargrid[(coord.0, coord.1 + j_offset)] = Some(*number);
                 }
             }
         }
 
-        let cog_coords = aoctk::grid::find_coords_for(&schematic, '*');
-        let ratio_sums: u64 = cog_coords
-            .iter()
-            .map(|(i, j)| {
-                let ratios: Vec<u32> =
-                    aoctk::grid::GridNeighbours::get_neighbours_for_coord(&geargrid, *i, *j)
-                        .into_vec()
-                        .iter()
-                        .filter_map(|item| item.unwrap())
-                        .collect::<HashSet<_>>()
-                        .into_iter()
-                        .collect::<Vec<_>>();
+        let ratio_sums: u64 = self
+            .schematic
+            .find_all(&'*')
+            .map(|pos| {
+                let ratios: Vec<u32> = geargrid
+                    .neighbours8(pos)
+                    .filter_map(|(_, item)| *item)
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .collect::<Vec<_>>();
 
                 if ratios.len() == 2 {
                     ratios.iter().map(|x| *x as u64).product()
@@ -136,6 +109,37 @@ mod tests {
             })
             .sum();
 
-        assert_eq!(ratio_sums, 73201705);
+        ratio_sums.into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day3_part1() {
+        let Some(input) = test_input(3) else {
+            return;
+        };
+
+        assert_eq!(
+            Day3::parse(&input).unwrap().part1(),
+            test_answer(3, Part::One)
+        );
+    }
+
+    #[test]
+    fn day3_part2() {
+        let Some(input) = test_input(3) else {
+            return;
+        };
+
+        assert_eq!(
+            Day3::parse(&input).unwrap().part2(),
+            test_answer(3, Part::Two)
+        );
     }
 }
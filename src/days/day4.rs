@@ -1,6 +1,11 @@
-use aoctk::io::read_string_col;
+use crate::{
+    error::{parse_lines, parse_token, ParseError},
+    solution::{Answer, Solution},
+};
 use regex::Regex;
-use std::path::Path;
+use std::collections::HashSet;
+
+const DAY: u8 = 4;
 
 #[derive(Debug)]
 pub struct Scratchcard {
@@ -9,51 +14,53 @@ pub struct Scratchcard {
     pub numbers: Vec<u32>,
 }
 
-pub fn parse_cards_from_file(path: &Path) -> Vec<Scratchcard> {
+pub fn parse_cards(input: &str) -> Result<Vec<Scratchcard>, ParseError> {
     let split_re = Regex::new("[ ]+").unwrap();
-    let card_re = Regex::new("Card[ ]+").unwrap();
-    let card_lines = read_string_col(path).expect("Unable to read card file. ");
-    card_lines
-        .iter()
-        .map(|line| {
-            let line_segs: Vec<&str> = line.split(": ").collect();
-            let game_id = card_re
-                .replace(line_segs.first().unwrap(), "")
-                .parse::<u32>()
-                .unwrap();
-            let number_strings: Vec<&str> = line_segs.last().unwrap().split(" | ").collect();
-            let winning_numbers = split_re
-                .split(
-                    number_strings
-                        .first()
-                        .expect("No winning numbers found. ")
-                        .trim(),
-                )
-                .map(|str| str.trim().parse().unwrap())
-                .collect();
-            let numbers = split_re
-                .split(number_strings.last().expect("No numbers found. ").trim())
-                .map(|str| str.trim().parse().unwrap())
-                .collect();
-            Scratchcard {
-                game_id,
-                winning_numbers,
-                numbers,
-            }
+    parse_lines(input, |line| {
+        let (card, number_str) = line
+            .split_once(": ")
+            .ok_or_else(|| ParseError::new(DAY, "expected `Card N: ...`").at(line, line))?;
+        let game_id = match card.strip_prefix("Card") {
+            Some(id) => parse_token(DAY, line, id)?,
+            None => return Err(ParseError::new(DAY, "expected `Card N`").at(line, card)),
+        };
+        let (winning_str, played_str) = number_str.split_once(" | ").ok_or_else(|| {
+            ParseError::new(
+                DAY,
+                "expected winning numbers and numbers separated by ` | `",
+            )
+            .at(line, number_str)
+        })?;
+        let winning_numbers = split_re
+            .split(winning_str.trim())
+            .map(|str| parse_token(DAY, line, str))
+            .collect::<Result<_, _>>()?;
+        let numbers = split_re
+            .split(played_str.trim())
+            .map(|str| parse_token(DAY, line, str))
+            .collect::<Result<_, _>>()?;
+        Ok(Scratchcard {
+            game_id,
+            winning_numbers,
+            numbers,
         })
-        .collect()
+    })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::parse_cards_from_file;
-    use std::{collections::HashSet, path::Path};
+pub struct Day4 {
+    cards: Vec<Scratchcard>,
+}
 
-    #[test]
-    fn day4_part1() {
-        let cards = parse_cards_from_file(Path::new("data/day4/data.txt"));
+impl Solution for Day4 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Day4 {
+            cards: parse_cards(input)?,
+        })
+    }
 
-        let scores: Vec<u32> = cards
+    fn part1(&self) -> Answer {
+        let scores: Vec<u32> = self
+            .cards
             .iter()
             .map(|card| {
                 let winning_nums: HashSet<u32> =
@@ -68,15 +75,14 @@ mod tests {
             })
             .collect();
 
-        assert_eq!(scores.iter().sum::<u32>(), 21158);
+        scores.iter().sum::<u32>().into()
     }
 
-    #[test]
-    fn day4_part2() {
-        let cards = parse_cards_from_file(Path::new("data/day4/data.txt"));
-        let mut card_copies = vec![1; cards.len()];
+    fn part2(&self) -> Answer {
+        let mut card_copies = vec![1; self.cards.len()];
 
-        let total_cards: Vec<usize> = cards
+        let total_cards: Vec<usize> = self
+            .cards
             .iter()
             .enumerate()
             .map(|(start, card)| {
@@ -92,6 +98,36 @@ mod tests {
             })
             .collect();
 
-        assert_eq!(total_cards.iter().sum::<usize>(), 6050769);
+        total_cards.iter().sum::<usize>().into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day4_part1() {
+        let Some(input) = test_input(4) else {
+            return;
+        };
+
+        assert_eq!(
+            Day4::parse(&input).unwrap().part1(),
+            test_answer(4, Part::One)
+        );
+    }
+
+    #[test]
+    fn day4_part2() {
+        let Some(input) = test_input(4) else {
+            return;
+        };
+
+        assert_eq!(
+            Day4::parse(&input).unwrap().part2(),
+            test_answer(4, Part::Two)
+        );
     }
 }
@@ -1,15 +1,15 @@
+use crate::{
+    error::{parse_token, ParseError},
+    solution::{Answer, Solution},
+};
 use regex::Regex;
-use std::path::Path;
-
-pub const MAP_ORDER: [&str; 7] = [
-    "seed-to-soil",
-    "soil-to-fertilizer",
-    "fertilizer-to-water",
-    "water-to-light",
-    "light-to-temperature",
-    "temperature-to-humidity",
-    "humidity-to-location",
-];
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    str::FromStr,
+};
+
+const DAY: u8 = 5;
 
 #[derive(Debug, Clone)]
 pub struct Range {
@@ -18,22 +18,50 @@ pub struct Range {
     pub length: i64,
 }
 
-impl Range {
-    pub fn parse(input: &str) -> Range {
+impl FromStr for Range {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Range, ParseError> {
         let split_re = Regex::new("[ ]+").unwrap();
         let segs: Vec<u64> = split_re
-            .split(input)
-            .map(|str| str.parse::<u64>().unwrap())
-            .collect();
-        Self {
+            .split(input.trim())
+            .map(|str| parse_token(DAY, input, str))
+            .collect::<Result<_, _>>()?;
+        if segs.len() != 3 {
+            return Err(ParseError::new(
+                DAY,
+                "expected a destination start, source start and length",
+            )
+            .at(input, input));
+        }
+        Ok(Self {
             src_start: segs[1] as i64,
             dest_start: segs[0] as i64,
             length: segs[2] as i64,
+        })
+    }
+}
+
+impl Range {
+    /// A range that maps each of `length` values from `start` onto itself.
+    pub fn identity(start: i64, length: i64) -> Range {
+        Range {
+            src_start: start,
+            dest_start: start,
+            length,
         }
     }
 
+    pub fn src_end(&self) -> i64 {
+        self.src_start + self.length
+    }
+
+    pub fn dest_end(&self) -> i64 {
+        self.dest_start + self.length
+    }
+
     pub fn contains(&self, val: i64) -> bool {
-        self.src_start < val && val < self.src_start + self.length
+        self.src_start <= val && val < self.src_end()
     }
 }
 
@@ -44,6 +72,43 @@ pub struct AlmanacMap {
 }
 
 impl AlmanacMap {
+    /// A map that takes every value to itself.
+    pub fn identity() -> AlmanacMap {
+        AlmanacMap {
+            ranges: vec![],
+            name: None,
+        }
+    }
+
+    pub fn ranges(&self) -> &[Range] {
+        &self.ranges
+    }
+
+    /// The categories that the map goes from and to, taken from a name like
+    /// `seed-to-soil`.
+    pub fn categories(&self) -> Option<(&str, &str)> {
+        self.name.as_deref()?.split_once("-to-")
+    }
+
+    /// A map that undoes this one, going from its destinations back to its
+    /// sources.
+    pub fn inverse(&self) -> AlmanacMap {
+        AlmanacMap {
+            ranges: self
+                .ranges
+                .iter()
+                .map(|r| Range {
+                    src_start: r.dest_start,
+                    dest_start: r.src_start,
+                    length: r.length,
+                })
+                .collect(),
+            name: self
+                .categories()
+                .map(|(from, to)| format!("{}-to-{}", to, from)),
+        }
+    }
+
     pub fn map(&self, source: u64) -> u64 {
         let res = self
             .ranges
@@ -86,108 +151,511 @@ impl AlmanacMap {
 
         res.unwrap_or(dest)
     }
+
+    /// Maps the destination values of each of `ranges` through this map,
+    /// keeping their sources. The ranges are split wherever they cross the
+    /// edge of one of this map's ranges, so that a chain of maps can be
+    /// followed a whole range at a time rather than value by value.
+    pub fn map_ranges(&self, ranges: &[Range]) -> Vec<Range> {
+        let mut sorted = self.ranges.clone();
+        sorted.sort_by_key(|r| r.src_start);
+
+        let mut mapped = vec![];
+        for range in ranges {
+            let (mut start, end) = (range.dest_start, range.dest_end());
+            // Takes a value in the destination of `range` back to its source.
+            let to_src = |val: i64| range.src_start + (val - range.dest_start);
+
+            for r in sorted.iter() {
+                if r.src_end() <= start {
+                    continue;
+                }
+                if r.src_start >= end {
+                    break;
+                }
+                // Values that no range covers are mapped to themselves.
+                if r.src_start > start {
+                    mapped.push(Range {
+                        src_start: to_src(start),
+                        dest_start: start,
+                        length: r.src_start - start,
+                    });
+                    start = r.src_start;
+                }
+                let piece_end = end.min(r.src_end());
+                mapped.push(Range {
+                    src_start: to_src(start),
+                    dest_start: r.dest_start + (start - r.src_start),
+                    length: piece_end - start,
+                });
+                start = piece_end;
+            }
+            if start < end {
+                mapped.push(Range {
+                    src_start: to_src(start),
+                    dest_start: start,
+                    length: end - start,
+                });
+            }
+        }
+        mapped
+    }
+
+    /// Builds a single map that gives the same result as this map followed
+    /// by `next`. The ranges of the new map are sorted by source and don't
+    /// overlap, and ranges that would map values onto themselves are left
+    /// out.
+    pub fn then(&self, next: &AlmanacMap) -> AlmanacMap {
+        // Cover every value, so that values this map leaves alone are still
+        // passed through `next`.
+        let mut sorted = self.ranges.clone();
+        sorted.sort_by_key(|r| r.src_start);
+        let mut covering = vec![];
+        let mut start = 0;
+        for r in sorted {
+            if r.src_start > start {
+                covering.push(Range::identity(start, r.src_start - start));
+            }
+            start = start.max(r.src_end());
+            covering.push(r);
+        }
+        covering.push(Range::identity(start, i64::MAX - start));
+
+        let mut ranges: Vec<Range> = vec![];
+        for r in next.map_ranges(&covering) {
+            if r.src_start == r.dest_start {
+                continue;
+            }
+            // Join ranges that carry on from each other.
+            match ranges.last_mut() {
+                Some(last) if last.src_end() == r.src_start && last.dest_end() == r.dest_start => {
+                    last.length += r.length;
+                }
+                _ => ranges.push(r),
+            }
+        }
+
+        let name = match (&self.name, &next.name) {
+            (Some(_), Some(_)) => match (self.categories(), next.categories()) {
+                (Some((from, _)), Some((_, to))) => Some(format!("{}-to-{}", from, to)),
+                _ => None,
+            },
+            (first, second) => first.clone().or_else(|| second.clone()),
+        };
+
+        AlmanacMap { ranges, name }
+    }
+
+    /// Composes a chain of maps into a single map, see [`AlmanacMap::then`].
+    pub fn compose<'a>(maps: impl IntoIterator<Item = &'a AlmanacMap>) -> AlmanacMap {
+        maps.into_iter()
+            .fold(AlmanacMap::identity(), |acc, map| acc.then(map))
+    }
 }
 
-pub fn parse_input(path: &Path) -> Result<(Vec<u64>, Vec<AlmanacMap>), aoctk::err::Error> {
-    let split_re = Regex::new("[ ]+").unwrap();
-    let input_chunks = aoctk::io::read_text_chunks(path)?;
-    let seeds = split_re
-        .split(
-            input_chunks
-                .first()
-                .unwrap()
-                .first()
-                .unwrap()
-                .replace("seeds: ", "")
-                .as_str(),
-        )
-        .map(|str| str.parse::<u64>().unwrap())
-        .collect();
-    let maps: Vec<AlmanacMap> = input_chunks
-        .iter()
-        .skip(1)
-        .map(|chunk| {
-            let name = chunk.first().unwrap().replace(" map:", "");
-            let ranges: Vec<Range> = chunk
-                .iter()
-                .skip(1)
-                .map(|line| Range::parse(line))
-                .collect();
-            AlmanacMap {
-                ranges: ranges,
-                name: Some(name),
+/// Writes the map out in the same format as the puzzle input.
+impl fmt::Display for AlmanacMap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} map:", self.name.as_deref().unwrap_or("unnamed"))?;
+        for r in self.ranges.iter() {
+            writeln!(f, "{} {} {}", r.dest_start, r.src_start, r.length)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlmanacError {
+    /// No map goes to or from the category.
+    UnknownCategory(String),
+    /// The categories are not joined by any chain of maps.
+    NoPath { from: String, to: String },
+    /// The map joins two categories that were already joined by other maps,
+    /// so there would be more than one way to get between them.
+    Cycle(String),
+}
+
+impl fmt::Display for AlmanacError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlmanacError::UnknownCategory(category) => {
+                write!(f, "no map uses the `{}` category", category)
+            }
+            AlmanacError::NoPath { from, to } => {
+                write!(f, "no chain of maps goes from `{}` to `{}`", from, to)
             }
+            AlmanacError::Cycle(name) => write!(
+                f,
+                "the `{}` map joins categories that are already joined",
+                name
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AlmanacError {}
+
+/// One step from a category to the next, through a map either forwards or,
+/// if `inverse` is set, backwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub map: usize,
+    pub inverse: bool,
+}
+
+/// The maps of an almanac, joined up into a graph of categories by their
+/// `X-to-Y` names.
+#[derive(Debug, Clone)]
+pub struct Almanac {
+    maps: Vec<AlmanacMap>,
+    /// The categories that can be reached in one step from each category.
+    links: HashMap<String, Vec<(String, Step)>>,
+}
+
+impl Almanac {
+    /// Builds the category graph. Every map must be named `X-to-Y`, and there
+    /// may only be one chain of maps between any two categories.
+    pub fn new(maps: Vec<AlmanacMap>) -> Result<Almanac, AlmanacError> {
+        let mut links: HashMap<String, Vec<(String, Step)>> = HashMap::new();
+        for (i, map) in maps.iter().enumerate() {
+            let name = map.name.clone().unwrap_or_default();
+            let (from, to) = map
+                .categories()
+                .ok_or_else(|| AlmanacError::UnknownCategory(name.clone()))?;
+
+            if from == to || find_path(&links, from, to).is_some() {
+                return Err(AlmanacError::Cycle(name));
+            }
+
+            let forward = Step {
+                map: i,
+                inverse: false,
+            };
+            let backward = Step {
+                map: i,
+                inverse: true,
+            };
+            links
+                .entry(from.to_string())
+                .or_default()
+                .push((to.to_string(), forward));
+            links
+                .entry(to.to_string())
+                .or_default()
+                .push((from.to_string(), backward));
+        }
+        Ok(Almanac { maps, links })
+    }
+
+    pub fn maps(&self) -> &[AlmanacMap] {
+        &self.maps
+    }
+
+    /// Finds the steps that go from one category to another.
+    pub fn path(&self, from: &str, to: &str) -> Result<Vec<Step>, AlmanacError> {
+        for category in [from, to] {
+            if !self.links.contains_key(category) {
+                return Err(AlmanacError::UnknownCategory(category.to_string()));
+            }
+        }
+
+        find_path(&self.links, from, to).ok_or_else(|| AlmanacError::NoPath {
+            from: from.to_string(),
+            to: to.to_string(),
         })
-        .collect();
+    }
+    /// Maps a value from one category to another, going backwards through
+    /// maps where needed.
+    pub fn convert(&self, value: u64, from: &str, to: &str) -> Result<u64, AlmanacError> {
+        Ok(self
+            .path(from, to)?
+            .iter()
+            .fold(value, |acc, step| match step.inverse {
+                false => self.maps[step.map].map(acc),
+                true => self.maps[step.map].rev_map(acc),
+            }))
+    }
+
+    /// Builds a single map from one category to another, see
+    /// [`AlmanacMap::compose`].
+    pub fn map_between(&self, from: &str, to: &str) -> Result<AlmanacMap, AlmanacError> {
+        let steps: Vec<AlmanacMap> = self
+            .path(from, to)?
+            .iter()
+            .map(|step| match step.inverse {
+                false => self.maps[step.map].clone(),
+                true => self.maps[step.map].inverse(),
+            })
+            .collect();
+        Ok(AlmanacMap::compose(&steps))
+    }
+}
+
+/// Searches outwards from `from` for the steps that lead to `to`.
+fn find_path(
+    links: &HashMap<String, Vec<(String, Step)>>,
+    from: &str,
+    to: &str,
+) -> Option<Vec<Step>> {
+    // Remember how each category was reached, so that the steps can be
+    // followed back.
+    let mut reached_by: HashMap<&str, Option<(&str, Step)>> = HashMap::from([(from, None)]);
+    let mut queue = VecDeque::from([from]);
+    while let Some(category) = queue.pop_front() {
+        if category == to {
+            let mut steps = vec![];
+            let mut current = to;
+            while let Some(Some((prev, step))) = reached_by.get(current) {
+                steps.push(*step);
+                current = prev;
+            }
+            steps.reverse();
+            return Some(steps);
+        }
+        for (next, step) in links.get(category).into_iter().flatten() {
+            if !reached_by.contains_key(next.as_str()) {
+                reached_by.insert(next, Some((category, *step)));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+pub fn parse_input(input: &str) -> Result<(Vec<u64>, Vec<AlmanacMap>), ParseError> {
+    let split_re = Regex::new("[ ]+").unwrap();
+    let mut lines = input.lines().enumerate();
+    let seed_line = match lines.next() {
+        Some((_, line)) => line,
+        None => return Err(ParseError::new(DAY, "no seeds found")),
+    };
+    let seeds = match seed_line.strip_prefix("seeds: ") {
+        Some(seed_str) => split_re
+            .split(seed_str.trim())
+            .map(|str| parse_token(DAY, seed_line, str))
+            .collect::<Result<_, _>>(),
+        None => Err(ParseError::new(DAY, "expected `seeds: ...`").at(seed_line, seed_line)),
+    }
+    .map_err(|err| err.on_line(1))?;
+
+    let mut maps: Vec<AlmanacMap> = Vec::new();
+    for (i, line) in lines {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        if let Some(name) = line.strip_suffix(" map:") {
+            if name.split_once("-to-").is_none() {
+                return Err(
+                    ParseError::new(DAY, "expected a map name like `seed-to-soil`")
+                        .at(line, name)
+                        .on_line(i + 1),
+                );
+            }
+            maps.push(AlmanacMap {
+                ranges: Vec::new(),
+                name: Some(name.to_string()),
+            });
+        } else {
+            let range = line.parse::<Range>().map_err(|err| err.on_line(i + 1))?;
+            match maps.last_mut() {
+                Some(map) => map.ranges.push(range),
+                None => {
+                    return Err(ParseError::new(DAY, "found a range before any map")
+                        .at(line, line)
+                        .on_line(i + 1))
+                }
+            }
+        }
+    }
     Ok((seeds, maps))
 }
 
+/// Reads the seeds as pairs of a start and a length, as in part 2. Gives
+/// `None` if the seeds don't pair up, or if any of the ranges is empty.
+pub fn seed_ranges(seeds: &[u64]) -> Option<Vec<Range>> {
+    if !seeds.len().is_multiple_of(2) {
+        return None;
+    }
+    let starts = seeds.iter().step_by(2);
+    let lengths = seeds.iter().skip(1).step_by(2);
+    starts
+        .zip(lengths)
+        .map(|(start, length)| {
+            (*length > 0).then(|| Range::identity(*start as i64, *length as i64))
+        })
+        .collect()
+}
+
+pub struct Day5 {
+    seeds: Vec<u64>,
+    /// The whole chain of maps, from seeds to locations, as a single map.
+    seed_to_location: AlmanacMap,
+}
+
+impl Solution for Day5 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let (seeds, maps) = parse_input(input)?;
+        let to_parse_error = |err: AlmanacError| ParseError::new(DAY, err.to_string());
+        let almanac = Almanac::new(maps).map_err(to_parse_error)?;
+
+        Ok(Day5 {
+            seeds,
+            seed_to_location: almanac
+                .map_between("seed", "location")
+                .map_err(to_parse_error)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        let output: Vec<u64> = self
+            .seeds
+            .iter()
+            .map(|seed| self.seed_to_location.map(*seed))
+            .collect();
+
+        output.into_iter().min().unwrap().into()
+    }
+
+    fn part2(&self) -> Answer {
+        // Part 1 can use any seeds, but part 2 needs them to pair up.
+        let Some(seed_ranges) = seed_ranges(&self.seeds) else {
+            return Answer::Unsolved;
+        };
+        let location_ranges = self.seed_to_location.map_ranges(&seed_ranges);
+
+        location_ranges
+            .iter()
+            .map(|range| range.dest_start)
+            .min()
+            .unwrap()
+            .into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
 
     #[test]
     fn day5_part1() {
-        let (seeds, maps) =
-            parse_input(Path::new("data/day5/data.txt")).expect("Unable to parse input. ");
-        let map_map: HashMap<_, _> = maps
-            .into_iter()
-            .map(|m| (m.name.clone().unwrap(), m.clone()))
-            .collect();
-        let output: Vec<u64> = seeds
-            .iter()
-            .map(|seed| {
-                MAP_ORDER
-                    .iter()
-                    .fold(*seed, |acc, map_key| map_map[*map_key].map(acc))
-            })
-            .collect();
+        let Some(input) = test_input(5) else {
+            return;
+        };
 
-        assert_eq!(output.into_iter().min().unwrap(), 621354867);
+        assert_eq!(
+            Day5::parse(&input).unwrap().part1(),
+            test_answer(5, Part::One)
+        );
     }
 
     #[test]
-    #[ignore = "long running test"]
     fn day5_part2() {
-        let (seeds, maps) =
-            parse_input(Path::new("data/day5/data.txt")).expect("Unable to parse input. ");
-        let seed_ranges: Vec<Range> = seeds
-            .chunks(2)
-            .into_iter()
-            .map(|range| Range {
-                src_start: range[0] as i64,
-                dest_start: 0,
-                length: range[1] as i64,
-            })
-            .collect();
-        let map_map: HashMap<_, _> = maps
-            .into_iter()
-            .map(|m| (m.name.clone().unwrap(), m.clone()))
+        let Some(input) = test_input(5) else {
+            return;
+        };
+
+        assert_eq!(
+            Day5::parse(&input).unwrap().part2(),
+            test_answer(5, Part::Two)
+        );
+    }
+
+    #[test]
+    fn map_ranges_splits_at_boundaries() {
+        let map = AlmanacMap {
+            ranges: vec!["50 98 2".parse().unwrap(), "52 50 48".parse().unwrap()],
+            name: None,
+        };
+        let mapped: Vec<(i64, i64, i64)> = map
+            .map_ranges(&[Range::identity(40, 70)])
+            .iter()
+            .map(|r| (r.src_start, r.dest_start, r.length))
             .collect();
-        let mut iter = (0..)
-            .map(|dest_test| {
-                if dest_test % 1000000 == 0 {
-                    println!("{}", dest_test);
-                }
-                let res = MAP_ORDER
-                    .iter()
-                    .rev()
-                    .fold(dest_test, |acc, map_key| map_map[*map_key].rev_map(acc));
-                let maps_to_seed_range = seed_ranges
-                    .iter()
-                    .map(|range| range.contains(res as i64))
-                    .any(|in_range| in_range);
-                if maps_to_seed_range {
-                    Some(dest_test)
-                } else {
-                    None
-                }
+        assert_eq!(
+            mapped,
+            vec![(40, 40, 10), (50, 52, 48), (98, 50, 2), (100, 100, 10)]
+        );
+    }
+
+    #[test]
+    fn composed_map_agrees_with_chain() {
+        let (_, maps) = parse_input(include_str!("../../samples/day5/example.txt")).unwrap();
+        let composed = AlmanacMap::compose(&maps);
+
+        assert_eq!(composed.name.as_deref(), Some("seed-to-location"));
+        assert!(composed
+            .ranges()
+            .windows(2)
+            .all(|pair| pair[0].src_end() <= pair[1].src_start));
+        for seed in (0..200).chain([1_000_000, 4_000_000_000]) {
+            let chained = maps.iter().fold(seed, |acc, map| map.map(acc));
+            assert_eq!(composed.map(seed), chained);
+            assert_eq!(composed.rev_map(chained), seed);
+        }
+    }
+
+    #[test]
+    fn convert_between_any_categories() {
+        let (_, maps) = parse_input(include_str!("../../samples/day5/example.txt")).unwrap();
+        let almanac = Almanac::new(maps.clone()).unwrap();
+
+        // Humidity is after soil in the chain, so this goes backwards.
+        let path = almanac.path("humidity", "soil").unwrap();
+        assert_eq!(path.len(), 5);
+        assert!(path.iter().all(|step| step.inverse));
+        for humidity in [0, 46, 78, 99] {
+            let soil = maps[1..6]
+                .iter()
+                .rev()
+                .fold(humidity, |acc, map| map.rev_map(acc));
+            assert_eq!(almanac.convert(humidity, "humidity", "soil"), Ok(soil));
+        }
+        assert_eq!(almanac.convert(79, "seed", "location"), Ok(82));
+    }
+
+    #[test]
+    fn almanac_errors() {
+        let (_, maps) = parse_input("seeds: 1\n\na-to-b map:\n\nc-to-d map:\n").unwrap();
+        let almanac = Almanac::new(maps).unwrap();
+        assert_eq!(
+            almanac.path("a", "d"),
+            Err(AlmanacError::NoPath {
+                from: "a".to_string(),
+                to: "d".to_string()
             })
-            .filter(|item| item.is_some());
-        let output = iter.next().unwrap();
+        );
+        assert_eq!(
+            almanac.path("a", "e"),
+            Err(AlmanacError::UnknownCategory("e".to_string()))
+        );
+
+        let (_, maps) =
+            parse_input("seeds: 1\n\na-to-b map:\n\nb-to-c map:\n\nc-to-a map:\n").unwrap();
+        assert_eq!(
+            Almanac::new(maps).unwrap_err(),
+            AlmanacError::Cycle("c-to-a".to_string())
+        );
+
+        let err = Day5::parse("seeds: 1\n\nseed-to-soil map:\n")
+            .err()
+            .unwrap();
+        assert_eq!(err.message, "no map uses the `location` category");
+    }
+
+    #[test]
+    fn unpaired_seeds() {
+        let maps = "\n\nseed-to-location map:\n0 1 2\n";
+        let day = Day5::parse(&format!("seeds: 79 14 55{}", maps)).unwrap();
+        assert_eq!(day.part1(), Answer::from(14));
+        assert_eq!(day.part2(), Answer::Unsolved);
+
+        let day = Day5::parse(&format!("seeds: 79 14 55 0{}", maps)).unwrap();
+        assert_eq!(day.part1(), Answer::from(0));
+        assert_eq!(day.part2(), Answer::Unsolved);
 
-        assert_eq!(output.unwrap(), 15880236);
+        let day = Day5::parse(&format!("seeds: 79 14 55 13{}", maps)).unwrap();
+        assert_eq!(day.part2(), Answer::from(55));
     }
 }
@@ -1,25 +1,104 @@
+use crate::{
+    error::{parse_token, ParseError},
+    solution::{Answer, Solution},
+};
+use num::{BigUint, CheckedSub, ToPrimitive};
 use regex::Regex;
-use std::{num::ParseIntError, path::Path};
+use std::ops::RangeInclusive;
+
+const DAY: u8 = 6;
 
 #[derive(Debug, Clone)]
 pub struct RaceRecord {
-    time_ms: u64,
-    dist: u64,
+    time_ms: u128,
+    dist: u128,
 }
 
-pub fn parse_races(path: &Path) -> Result<Vec<RaceRecord>, aoctk::err::Error> {
+impl RaceRecord {
+    pub fn new(time_ms: u128, dist: u128) -> RaceRecord {
+        RaceRecord { time_ms, dist }
+    }
+
+    fn beats_record(&self, hold: u128) -> bool {
+        // A distance too far for a `u128` is further than any record.
+        hold.checked_mul(self.time_ms - hold)
+            .is_none_or(|dist| dist > self.dist)
+    }
+
+    /// The hold times that go further than the record. Holding for `hold`
+    /// ms goes `hold * (time - hold)` mm, so the holds that tie with the
+    /// record are the roots of a quadratic, and every hold strictly between
+    /// them wins. The square of the time can be too large for a `u128`, so
+    /// the roots are found with big integers. Returns `None` if no hold wins.
+    pub fn ways_to_win(&self) -> Option<RangeInclusive<u128>> {
+        let time = self.time_ms;
+        let big_time = BigUint::from(time);
+        let discrim = (&big_time * &big_time).checked_sub(&(BigUint::from(self.dist) * 4u32))?;
+        // The square root is at most the time, so it always fits.
+        let root = discrim.sqrt().to_u128()?;
+
+        // The lower root is `(time - sqrt(discrim)) / 2`. Rounding the square
+        // root down can only put this at or below the shortest winning hold,
+        // which is then found by stepping up past any ties.
+        let mut shortest = (time - root) / 2;
+        while !self.beats_record(shortest) {
+            shortest += 1;
+            if shortest > time / 2 {
+                return None;
+            }
+        }
+
+        // The distances are symmetric about half the race time.
+        Some(shortest..=time - shortest)
+    }
+
+    pub fn count_ways_to_win(&self) -> u128 {
+        self.ways_to_win()
+            .map_or(0, |holds| holds.end() - holds.start() + 1)
+    }
+}
+
+/// Finds the line with the given label, returning it along with the values
+/// that follow the label.
+fn labelled_line<'a>(
+    input: &'a str,
+    index: usize,
+    label: &str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    let line = input
+        .lines()
+        .nth(index)
+        .ok_or_else(|| ParseError::new(DAY, format!("missing the `{}` line", label)))?;
+    let values = line
+        .strip_prefix(label)
+        .and_then(|rest| rest.strip_prefix(":"))
+        .ok_or_else(|| {
+            ParseError::new(DAY, format!("expected `{}: ...`", label))
+                .at(line, line)
+                .on_line(index + 1)
+        })?;
+    Ok((line, values))
+}
+
+fn parse_values(input: &str, index: usize, label: &str) -> Result<Vec<u128>, ParseError> {
     let split_re = Regex::new("[ ]+").unwrap();
-    let lines = aoctk::io::read_string_col(path)?;
-    let times: Vec<u64> = split_re
-        .split(lines[0].replace("Time: ", "").trim())
-        .map(|str| str.parse::<u64>())
-        .map(std::convert::identity)
-        .collect::<Result<Vec<u64>, _>>()?;
-    let distances: Vec<u64> = split_re
-        .split(lines[1].replace("Distance: ", "").trim())
-        .map(|str| str.parse::<u64>())
-        .map(std::convert::identity)
-        .collect::<Result<Vec<u64>, _>>()?;
+    let (line, values) = labelled_line(input, index, label)?;
+    split_re
+        .split(values.trim())
+        .map(|str| parse_token(DAY, line, str))
+        .collect::<Result<Vec<u128>, _>>()
+        .map_err(|err| err.on_line(index + 1))
+}
+
+pub fn parse_races(input: &str) -> Result<Vec<RaceRecord>, ParseError> {
+    let times = parse_values(input, 0, "Time")?;
+    let distances = parse_values(input, 1, "Distance")?;
+    if times.len() != distances.len() {
+        return Err(ParseError::new(
+            DAY,
+            "there must be the same number of times and distances",
+        ));
+    }
 
     Ok(times
         .into_iter()
@@ -28,58 +107,115 @@ pub fn parse_races(path: &Path) -> Result<Vec<RaceRecord>, aoctk::err::Error> {
         .collect())
 }
 
-pub fn parse_race_pt2(path: &Path) -> RaceRecord {
-    let lines = aoctk::io::read_string_col(path).unwrap();
-    let time: u64 = lines[0]
-        .split_once(":")
-        .unwrap()
-        .1
-        .replace(" ", "")
-        .parse::<u64>()
-        .unwrap();
-    let disance: u64 = lines[1]
-        .split_once(":")
-        .unwrap()
-        .1
-        .replace(" ", "")
-        .parse::<u64>()
-        .unwrap();
-
-    RaceRecord {
+/// Parses the single race for part 2, where the spaces between the digits
+/// are ignored.
+fn parse_kerned_value(input: &str, index: usize, label: &str) -> Result<u128, ParseError> {
+    let (line, values) = labelled_line(input, index, label)?;
+    values.replace(" ", "").parse::<u128>().map_err(|_| {
+        ParseError::new(DAY, "expected a number")
+            .at(line, values.trim())
+            .on_line(index + 1)
+    })
+}
+
+pub fn parse_race_pt2(input: &str) -> Result<RaceRecord, ParseError> {
+    let time = parse_kerned_value(input, 0, "Time")?;
+    let disance = parse_kerned_value(input, 1, "Distance")?;
+
+    Ok(RaceRecord {
         time_ms: time,
         dist: disance,
+    })
+}
+
+pub struct Day6 {
+    races: Vec<RaceRecord>,
+    race: RaceRecord,
+}
+
+impl Solution for Day6 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Day6 {
+            races: parse_races(input)?,
+            race: parse_race_pt2(input)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        self.races
+            .iter()
+            .map(RaceRecord::count_ways_to_win)
+            .try_fold(1u128, |product, count| product.checked_mul(count))
+            .map_or(Answer::Unsolved, Answer::from)
+    }
+
+    fn part2(&self) -> Answer {
+        self.race.count_ways_to_win().into()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
 
     #[test]
     fn day6_part1() {
-        let race_records = parse_races(Path::new("data/day6/data.txt")).unwrap();
-        let res: Vec<u64> = race_records
-            .iter()
-            .map(|race| {
-                // Spotted this was a quadratic. Yay!
-                let discrim = (race.time_ms.pow(2) - 4 * (race.dist)) as f64;
-                let x1 = (race.time_ms as f64 + discrim.sqrt()) / 2.0;
-                let x2 = (race.time_ms as f64 - discrim.sqrt()) / 2.0;
-                x1 as u64 - x2 as u64
-            })
-            .collect();
-
-        assert_eq!(res.iter().product::<u64>(), 160816);
+        let Some(input) = test_input(6) else {
+            return;
+        };
+
+        assert_eq!(
+            Day6::parse(&input).unwrap().part1(),
+            test_answer(6, Part::One)
+        );
     }
 
     #[test]
     fn day6_part2() {
-        let race_record = parse_race_pt2(Path::new("data/day6/data.txt"));
-        let discrim = (race_record.time_ms.pow(2) - 4 * (race_record.dist)) as f64;
-        let x1 = (race_record.time_ms as f64 + discrim.sqrt()) / 2.0;
-        let x2 = (race_record.time_ms as f64 - discrim.sqrt()) / 2.0;
-        let res = x1 as u64 - x2 as u64;
+        let Some(input) = test_input(6) else {
+            return;
+        };
+
+        assert_eq!(
+            Day6::parse(&input).unwrap().part2(),
+            test_answer(6, Part::Two)
+        );
+    }
+
+    #[test]
+    fn ties_do_not_win() {
+        // The holds of 10 and 20 ms both go exactly 200 mm.
+        assert_eq!(RaceRecord::new(30, 200).ways_to_win(), Some(11..=19));
+        assert_eq!(RaceRecord::new(7, 9).ways_to_win(), Some(2..=5));
+        // Only a hold of 2 ms ties, and nothing beats it.
+        assert_eq!(RaceRecord::new(4, 4).ways_to_win(), None);
+        assert_eq!(RaceRecord::new(4, 5).count_ways_to_win(), 0);
+    }
+
+    #[test]
+    fn large_races_are_exact() {
+        let time = u64::MAX as u128;
+        let hold = 1 << 40;
+        let race = RaceRecord::new(time, hold * (time - hold));
+        assert_eq!(race.ways_to_win(), Some(hold + 1..=time - hold - 1));
+    }
+
+    #[test]
+    fn races_near_the_largest_time() {
+        // A hold of 1 ms goes `time - 1` mm, which doesn't beat either record,
+        // but every longer hold up to 2 ms from the end of the race does.
+        let time = u128::MAX;
+        for dist in [time - 1, time] {
+            let race = RaceRecord::new(time, dist);
+            assert_eq!(race.ways_to_win(), Some(2..=time - 2));
+            assert_eq!(race.count_ways_to_win(), time - 3);
+        }
+        assert_eq!(RaceRecord::new(time, 1).ways_to_win(), Some(1..=time - 1));
+        assert_eq!(RaceRecord::new(10, u128::MAX).ways_to_win(), None);
 
-        assert_eq!(res, 46561107);
+        // The count is too large to be a number.
+        let day = Day6::parse(&format!("Time: {}\nDistance: {}", time, time)).unwrap();
+        assert_eq!(day.part2(), Answer::Text((time - 3).to_string()));
     }
 }
@@ -1,10 +1,144 @@
+use crate::{
+    error::{column_of, parse_lines, parse_token, ParseError},
+    solution::{Answer, Solution},
+};
 use std::{cmp::Ordering, collections::HashMap};
 
-const INPUT: &str = include_str!("../../data/day7/data.txt");
+const DAY: u8 = 7;
 
 const CHAR_ORDER: &str = "23456789TJQKA";
 const CHAR_ORDER_PT2: &str = "J23456789TQKA";
 
+/// The rules that hands are played by.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CardRules {
+    /// Every card in the deck, from the weakest to the strongest.
+    pub order: String,
+    /// Cards that act as whichever card makes the strongest hand. They are
+    /// still ranked by `order` when breaking ties.
+    pub wildcards: String,
+    pub hand_size: usize,
+}
+
+impl CardRules {
+    /// The rules for part 1.
+    pub fn standard() -> CardRules {
+        CardRules {
+            order: CHAR_ORDER.to_string(),
+            wildcards: String::new(),
+            hand_size: 5,
+        }
+    }
+
+    /// The rules for part 2, where `J` is a joker and the weakest card.
+    pub fn jokers() -> CardRules {
+        CardRules {
+            order: CHAR_ORDER_PT2.to_string(),
+            wildcards: "J".to_string(),
+            hand_size: 5,
+        }
+    }
+
+    pub fn strength(&self, card: char) -> Option<usize> {
+        self.order.chars().position(|c| c == card)
+    }
+
+    pub fn is_wild(&self, card: char) -> bool {
+        self.wildcards.contains(card)
+    }
+
+    /// Parses a hand, checking that it has the right number of cards and
+    /// that they are all in the deck.
+    pub fn parse_hand(&self, input: &str) -> Result<Hand, ParseError> {
+        if let Some((offset, c)) = input
+            .char_indices()
+            .find(|(_, c)| self.strength(*c).is_none())
+        {
+            return Err(ParseError::new(DAY, "unknown card")
+                .at(input, &input[offset..offset + c.len_utf8()]));
+        }
+        if input.chars().count() != self.hand_size {
+            return Err(
+                ParseError::new(DAY, format!("a hand must have {} cards", self.hand_size))
+                    .at(input, input),
+            );
+        }
+
+        Ok(Hand::new(input, self))
+    }
+
+    pub fn compare_cards(&self, hand1: &str, hand2: &str) -> Ordering {
+        hand1
+            .chars()
+            .zip(hand2.chars())
+            .map(|(c1, c2)| self.strength(c1).cmp(&self.strength(c2)))
+            .find(|order| *order != Ordering::Equal)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+/// A hand that has been evaluated under a set of rules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hand {
+    pub cards: String,
+    /// The size of each group of matching cards, largest first. The
+    /// wildcards are counted in with the largest group.
+    pub counts: Vec<usize>,
+    pub category: HandType,
+    /// Orders hands from the weakest to the strongest. It is the category's
+    /// ranking followed by the strength of each card in turn.
+    pub key: Vec<usize>,
+}
+
+impl Hand {
+    /// Evaluates a hand. The cards are assumed to be in the deck, see
+    /// [`CardRules::parse_hand`].
+    pub fn new(cards: &str, rules: &CardRules) -> Hand {
+        let mut groups: HashMap<char, usize> = HashMap::new();
+        let mut wildcards = 0;
+        for card in cards.chars() {
+            if rules.is_wild(card) {
+                wildcards += 1;
+            } else {
+                *groups.entry(card).or_default() += 1;
+            }
+        }
+
+        let mut counts: Vec<usize> = groups.into_values().collect();
+        counts.sort_by(|a, b| b.cmp(a));
+        // The category only depends on the two largest groups. Adding every
+        // wildcard to the largest group makes it as large as it can be
+        // without shrinking the second, so this always gives the best hand.
+        match counts.first_mut() {
+            Some(largest) => *largest += wildcards,
+            None => counts.push(wildcards),
+        }
+
+        let category = HandType::from_counts(&counts, cards);
+        let key = std::iter::once(category.ranking())
+            .chain(cards.chars().map(|c| rules.strength(c).unwrap_or(0)))
+            .collect();
+        Hand {
+            cards: cards.to_string(),
+            counts,
+            category,
+            key,
+        }
+    }
+}
+
+impl PartialOrd for Hand {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Hand {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key.cmp(&other.key)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HandType {
     /// All five cards are the same label
@@ -24,26 +158,21 @@ pub enum HandType {
 }
 
 impl HandType {
-    pub fn parse(input: &String) -> HandType {
-        let mut char_occurances: HashMap<char, u64> = HashMap::new();
-        for character in input.chars() {
-            char_occurances.insert(character, char_occurances.get(&character).unwrap_or(&0) + 1);
-        }
-
-        let mut char_occurances_vec = char_occurances.values().collect::<Vec<&u64>>();
-        char_occurances_vec.sort();
-        match char_occurances_vec.as_slice() {
-            [1, 1, 1, 1, 1] => HandType::HighCard(input.clone()),
-            [1, 1, 1, 2] => HandType::OnePair(input.clone()),
-            [1, 2, 2] => HandType::TwoPair(input.clone()),
-            [1, 1, 3] => HandType::ThreeOfAKind(input.clone()),
-            [2, 3] => HandType::FullHouse(input.clone()),
-            [1, 4] => HandType::FourOfAKind(input.clone()),
-            [5] => HandType::FiveOfAKind(input.clone()),
-            _ => {
-                println!("{:?}", char_occurances_vec);
-                panic!("Invalid hand");
-            }
+    /// Works out the type of a hand from the sizes of its groups of
+    /// matching cards, largest first. Only the two largest groups matter,
+    /// which lets hands of other sizes be typed as well.
+    pub fn from_counts(counts: &[usize], cards: &str) -> HandType {
+        let largest = counts.first().copied().unwrap_or(0);
+        let second = counts.get(1).copied().unwrap_or(0);
+        let cards = cards.to_string();
+        match (largest, second) {
+            (5.., _) => HandType::FiveOfAKind(cards),
+            (4, _) => HandType::FourOfAKind(cards),
+            (3, 2..) => HandType::FullHouse(cards),
+            (3, _) => HandType::ThreeOfAKind(cards),
+            (2, 2) => HandType::TwoPair(cards),
+            (2, _) => HandType::OnePair(cards),
+            _ => HandType::HighCard(cards),
         }
     }
 
@@ -70,139 +199,202 @@ impl HandType {
             HandType::HighCard(cards) => cards,
         }
     }
-
-    pub fn set_cards(&mut self, input_cards: &String) {
-        match self {
-            HandType::FiveOfAKind(cards) => *cards = input_cards.clone(),
-            HandType::FourOfAKind(cards) => *cards = input_cards.clone(),
-            HandType::FullHouse(cards) => *cards = input_cards.clone(),
-            HandType::ThreeOfAKind(cards) => *cards = input_cards.clone(),
-            HandType::TwoPair(cards) => *cards = input_cards.clone(),
-            HandType::OnePair(cards) => *cards = input_cards.clone(),
-            HandType::HighCard(cards) => *cards = input_cards.clone(),
-        }
-    }
 }
 
-impl PartialOrd for HandType {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+/// Ranks the hands from 1 for the weakest, by sorting them. Hands that are
+/// equally strong share a rank.
+pub fn rank(hands: &[Hand]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..hands.len()).collect();
+    order.sort_unstable_by(|&a, &b| hands[a].cmp(&hands[b]));
+
+    let mut ranks: Vec<usize> = vec![0; hands.len()];
+    for (position, &i) in order.iter().enumerate() {
+        ranks[i] = match position {
+            0 => 1,
+            _ if hands[order[position - 1]].key == hands[i].key => ranks[order[position - 1]],
+            _ => position + 1,
+        };
     }
+    ranks
 }
 
-impl Ord for HandType {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        match self.ranking().cmp(&other.ranking()) {
-            Ordering::Equal => compare_cards(&self.cards(), &other.cards()),
-            other => other,
-        }
-    }
+pub struct Day7 {
+    hands: Vec<String>,
+    bids: Vec<u64>,
 }
 
-pub fn rank(hands: &Vec<HandType>) -> Vec<usize> {
-    let mut ranks: Vec<usize> = vec![1; hands.len()];
-    for (i, hand) in hands.iter().enumerate() {
-        for (j, other_hand) in hands.iter().enumerate() {
-            if i == j {
-                continue;
-            }
-            if hand > other_hand {
-                ranks[i] += 1;
-            }
-        }
+impl Day7 {
+    fn winnings(&self, rules: &CardRules) -> u64 {
+        let hands = self
+            .hands
+            .iter()
+            .map(|hand| Hand::new(hand, rules))
+            .collect::<Vec<Hand>>();
+        let ranks = rank(&hands);
+        self.bids
+            .iter()
+            .zip(&ranks)
+            .map(|(bid, rank)| bid * *rank as u64)
+            .sum()
     }
-    ranks
 }
 
-pub fn compare_cards(hand1: &String, hand2: &String) -> std::cmp::Ordering {
-    let out: Vec<Ordering> = hand1
-        .chars()
+impl Solution for Day7 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let rules = CardRules::standard();
+        let (hands, bids): (Vec<String>, Vec<u64>) = parse_lines(input, |line| {
+            let segs: Vec<&str> = line.split_whitespace().collect();
+            if segs.len() != 2 {
+                return Err(ParseError::new(DAY, "expected `<hand> <bid>`").at(line, line));
+            }
+            let hand = rules
+                .parse_hand(segs[0])
+                .map_err(|err| err.shift(column_of(line, segs[0]) - 1))?;
+            let bid: u64 = parse_token(DAY, line, segs[1])?;
+            Ok((hand.cards, bid))
+        })?
         .into_iter()
-        .zip(hand2.chars().into_iter())
-        .map(|(c1, c2)| {
-            CHAR_ORDER
-                .chars()
-                .position(|c| c == c1)
-                .unwrap()
-                .cmp(&CHAR_ORDER.chars().position(|c| c == c2).unwrap())
-        })
-        .filter(|order| *order != Ordering::Equal)
-        .collect();
+        .unzip();
+        Ok(Day7 { hands, bids })
+    }
 
-    if out.len() == 0 {
-        Ordering::Equal
-    } else {
-        out[0]
+    fn part1(&self) -> Answer {
+        self.winnings(&CardRules::standard()).into()
     }
-}
 
-pub fn optimise_next_joker(hand: &String) -> HandType {
-    let mut char_occurances: HashMap<char, u64> = HashMap::new();
-    for character in hand.chars() {
-        char_occurances.insert(character, char_occurances.get(&character).unwrap_or(&0) + 1);
-    }
-
-    // This isn't nice, but it works for parts 2.
-    // This took me so long to fix - I should have read the instructions more carefully.
-    // Also, should not have over engineered the solution.
-    char_occurances.remove(&'J');
-    match char_occurances.iter().max_by_key(|entry| entry.1) {
-        Some((c, _)) => {
-            let mut optim_hand = HandType::parse(&hand.replace("J", &c.to_string()));
-            optim_hand.set_cards(hand);
-            optim_hand
-        }
-        None => HandType::FiveOfAKind("JJJJJ".to_string()),
+    fn part2(&self) -> Answer {
+        self.winnings(&CardRules::jokers()).into()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
 
     #[test]
     fn day7_part1() {
-        let (hands, bids): (Vec<String>, Vec<u64>) = INPUT
-            .split("\n")
-            .map(|line| {
-                let segs: Vec<&str> = line.split_whitespace().collect();
-                let bid: u64 = segs[1].parse().unwrap();
-                (String::from(segs[0]), bid)
-            })
-            .unzip();
-        let hand_types: Vec<HandType> = hands.iter().map(HandType::parse).collect();
-        let ranks = rank(&hand_types);
-        let winnings: u64 = bids
-            .into_iter()
-            .zip(&ranks)
-            .map(|(bid, rank)| bid * *rank as u64)
-            .sum();
+        let Some(input) = test_input(7) else {
+            return;
+        };
 
-        assert_eq!(winnings, 246912307);
+        assert_eq!(
+            Day7::parse(&input).unwrap().part1(),
+            test_answer(7, Part::One)
+        );
     }
 
     #[test]
-    #[ignore = "You need to change the CHAR_ORDER to CHAR_ORDER_PT2 to run this test"]
     fn day7_part2() {
-        let (hands, bids): (Vec<String>, Vec<u64>) = INPUT
-            .split("\n")
-            .map(|line| {
-                let segs: Vec<&str> = line.split_whitespace().collect();
-                let bid: u64 = segs[1].parse().unwrap();
-                (String::from(segs[0]), bid)
-            })
-            .unzip();
-        let opt_hands = hands
+        let Some(input) = test_input(7) else {
+            return;
+        };
+
+        assert_eq!(
+            Day7::parse(&input).unwrap().part2(),
+            test_answer(7, Part::Two)
+        );
+    }
+
+    #[test]
+    fn rules_side_by_side() {
+        let standard = CardRules::standard();
+        let jokers = CardRules::jokers();
+
+        let (kk, jj) = ("KTJJT", "QQQJA");
+        let kk_standard = standard.parse_hand(kk).unwrap();
+        let jj_standard = standard.parse_hand(jj).unwrap();
+        assert_eq!(kk_standard.category, HandType::TwoPair(kk.to_string()));
+        assert!(kk_standard < jj_standard);
+
+        let kk_jokers = jokers.parse_hand(kk).unwrap();
+        let jj_jokers = jokers.parse_hand(jj).unwrap();
+        assert_eq!(kk_jokers.category, HandType::FourOfAKind(kk.to_string()));
+        assert_eq!(kk_jokers.counts, vec![4, 1]);
+        assert!(kk_jokers > jj_jokers);
+    }
+
+    #[test]
+    fn custom_deck() {
+        let rules = CardRules {
+            order: "*abc".to_string(),
+            wildcards: "*".to_string(),
+            hand_size: 3,
+        };
+        assert_eq!(
+            rules.parse_hand("a*b").unwrap().category,
+            HandType::OnePair("a*b".to_string())
+        );
+        assert_eq!(
+            rules.parse_hand("***").unwrap().category,
+            HandType::ThreeOfAKind("***".to_string())
+        );
+        assert!(rules.parse_hand("abcd").is_err());
+        assert!(rules.parse_hand("abd").is_err());
+    }
+
+    /// Every hand that can be made from `deck`.
+    fn all_hands(deck: &str, size: usize) -> Vec<String> {
+        (0..size).fold(vec![String::new()], |hands, _| {
+            hands
+                .iter()
+                .flat_map(|hand| deck.chars().map(move |c| format!("{}{}", hand, c)))
+                .collect()
+        })
+    }
+
+    #[test]
+    fn wildcards_give_the_best_category() {
+        let rules = CardRules {
+            order: "J234".to_string(),
+            wildcards: "J".to_string(),
+            hand_size: 5,
+        };
+        let plain = CardRules {
+            wildcards: String::new(),
+            ..rules.clone()
+        };
+
+        for cards in all_hands(&rules.order, rules.hand_size) {
+            // Try every card in place of each joker.
+            let swaps = cards.chars().fold(vec![String::new()], |swaps, c| {
+                let options: Vec<char> = match c {
+                    'J' => "234".chars().collect(),
+                    _ => vec![c],
+                };
+                swaps
+                    .iter()
+                    .flat_map(|swap| options.iter().map(move |o| format!("{}{}", swap, o)))
+                    .collect()
+            });
+            let best = swaps
+                .iter()
+                .map(|swap| Hand::new(swap, &plain).category.ranking())
+                .max()
+                .unwrap();
+            assert_eq!(
+                Hand::new(&cards, &rules).category.ranking(),
+                best,
+                "{}",
+                cards
+            );
+        }
+    }
+
+    #[test]
+    fn sorted_ranks_match_pairwise_ranks() {
+        let rules = CardRules::jokers();
+        let hands: Vec<Hand> = all_hands("JT9", 5)
             .iter()
-            .map(|hand| optimise_next_joker(hand))
-            .collect::<Vec<HandType>>();
-        let ranks = rank(&opt_hands);
-        let winnings: u64 = bids
-            .into_iter()
-            .zip(&ranks)
-            .map(|(bid, rank)| bid * *rank as u64)
-            .sum();
+            .chain(all_hands("AK", 5).iter())
+            .chain(["JT9AK", "JT9AK"].map(String::from).iter())
+            .map(|cards| Hand::new(cards, &rules))
+            .collect();
 
-        assert_eq!(winnings, 246894760);
+        let pairwise: Vec<usize> = hands
+            .iter()
+            .map(|hand| 1 + hands.iter().filter(|other| hand > other).count())
+            .collect();
+        assert_eq!(rank(&hands), pairwise);
     }
 }
@@ -1,111 +1,419 @@
-use num::integer::lcm;
+use crate::{
+    dot::Digraph,
+    error::ParseError,
+    solution::{Answer, Solution},
+};
+use num::integer::{ExtendedGcd, Integer};
 use rayon::prelude::*;
 use regex::Regex;
-use std::path::Path;
+use std::collections::HashMap;
 
-const INPUT: &str = include_str!("../../data/day8/data.txt");
+const DAY: u8 = 8;
 
-pub fn parse_input() -> (String, Vec<String>, Vec<(String, String)>) {
-    let input_str = INPUT;
+/// The input as it is written, before the edges are matched up to nodes.
+#[derive(Debug, Clone)]
+pub struct RawNetwork {
+    pub instructions: String,
+    pub nodes: Vec<String>,
+    /// The names of the left and right nodes from each node.
+    pub edges: Vec<(String, String)>,
+}
+
+pub fn parse_input(input_str: &str) -> Result<RawNetwork, ParseError> {
     let instructions = input_str
         .lines()
-        .collect::<Vec<&str>>()
-        .first()
-        .unwrap()
+        .next()
+        .filter(|line| !line.is_empty())
+        .ok_or_else(|| ParseError::new(DAY, "missing the instructions"))?
         .to_string();
-    let nodes = input_str
-        .lines()
-        .skip(2)
-        .map(|line| line.split(" = ").next().unwrap().to_string())
-        .collect();
-    let match_patter = Regex::new(r"[ ]*([0-9A-Z]+), ([0-9A-Z]+)\)[ ]*").unwrap();
-    let edges = input_str
-        .lines()
-        .skip(2)
-        .map(|line| {
-            let caps = match_patter.captures(line).unwrap();
-            (caps[1].to_string(), caps[2].to_string())
-        })
-        .collect();
-    (instructions, nodes, edges)
+    if let Some((offset, c)) = instructions
+        .char_indices()
+        .find(|(_, c)| *c != 'L' && *c != 'R')
+    {
+        return Err(ParseError::new(DAY, "expected an `L` or `R` instruction")
+            .on_line(1)
+            .at(&instructions, &instructions[offset..offset + c.len_utf8()]));
+    }
+
+    let match_patter = Regex::new(r"^([0-9A-Z]+) = \(([0-9A-Z]+), ([0-9A-Z]+)\)[ ]*$").unwrap();
+    let mut nodes = vec![];
+    let mut edges = vec![];
+    for (i, line) in input_str.lines().enumerate().skip(2) {
+        let caps = match_patter.captures(line).ok_or_else(|| {
+            ParseError::new(DAY, "expected `NODE = (LEFT, RIGHT)`")
+                .on_line(i + 1)
+                .at(line, line)
+        })?;
+        nodes.push(caps[1].to_string());
+        edges.push((caps[2].to_string(), caps[3].to_string()));
+    }
+    Ok(RawNetwork {
+        instructions,
+        nodes,
+        edges,
+    })
 }
 
 pub fn match_edges_to_nodes(
-    nodes: &Vec<String>,
-    edges: &Vec<(String, String)>,
-) -> Vec<(usize, usize)> {
+    nodes: &[String],
+    edges: &[(String, String)],
+) -> Result<Vec<(usize, usize)>, ParseError> {
+    let ids: HashMap<&str, usize> = nodes
+        .iter()
+        .enumerate()
+        .map(|(id, node)| (node.as_str(), id))
+        .collect();
+    let find_node = |name: &String, line: usize| {
+        ids.get(name.as_str()).copied().ok_or_else(|| {
+            let mut err = ParseError::new(DAY, "edge points at an unknown node").on_line(line);
+            err.text = name.clone();
+            err
+        })
+    };
     edges
         .iter()
-        .map(|(left, right)| {
-            let left_index = nodes.iter().position(|node| node == left).unwrap();
-            let right_index = nodes.iter().position(|node| node == right).unwrap();
-            (left_index, right_index)
+        .enumerate()
+        .map(|(i, (left, right))| {
+            // The nodes start on the third line of the input.
+            let left_index = find_node(left, i + 3)?;
+            let right_index = find_node(right, i + 3)?;
+            Ok((left_index, right_index))
         })
         .collect()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// The steps at which a ghost walking from one node is on an end node. After
+/// `offset` steps the ghost is going round a loop that takes `period` steps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GhostCycle {
+    pub offset: usize,
+    pub period: usize,
+    /// The steps before the loop starts at which the ghost is on an end node.
+    pub prefix_hits: Vec<usize>,
+    /// The steps during the first time round the loop at which the ghost is
+    /// on an end node. It is on one again every `period` steps after each.
+    pub cycle_hits: Vec<usize>,
+}
 
-    #[test]
-    fn day8_part1() {
-        let (instructions, nodes, edges) = parse_input();
-        let matched_edges = match_edges_to_nodes(&nodes, &edges);
-
-        let mut i = 0;
-        let mut pos = nodes.iter().position(|node| node == "AAA").unwrap();
-        let dest = nodes.iter().position(|node| node == "ZZZ").unwrap();
-        while pos != dest {
-            let instruction = instructions.chars().nth(i % instructions.len()).unwrap();
-            pos = match instruction {
-                'L' => matched_edges[pos].0,
-                'R' => matched_edges[pos].1,
-                _ => panic!("Unknown direction"),
-            };
-            i += 1;
+impl GhostCycle {
+    pub fn hits_at(&self, step: usize) -> bool {
+        if step < self.offset {
+            self.prefix_hits.contains(&step)
+        } else {
+            let looped = self.offset + (step - self.offset) % self.period;
+            self.cycle_hits.contains(&looped)
         }
+    }
 
-        assert_eq!(i, 11911);
+    pub fn first_hit(&self) -> Option<usize> {
+        self.prefix_hits
+            .first()
+            .or(self.cycle_hits.first())
+            .copied()
     }
+}
 
-    #[test]
-    fn day8_part2() {
-        let (instructions, nodes, edges) = parse_input();
-        let matched_edges = match_edges_to_nodes(&nodes, &edges);
-        let starts: Vec<usize> = nodes
+/// Combines `x = a1 (mod n1)` and `x = a2 (mod n2)` into a single congruence,
+/// where the moduli don't have to be coprime. Returns `None` if no `x` meets
+/// both.
+pub fn crt_pair((a1, n1): (i128, i128), (a2, n2): (i128, i128)) -> Option<(i128, i128)> {
+    let ExtendedGcd { gcd, x, .. } = n1.extended_gcd(&n2);
+    if (a2 - a1) % gcd != 0 {
+        return None;
+    }
+    let lcm = n1 / gcd * n2;
+    let k = ((a2 - a1) / gcd * x).rem_euclid(n2 / gcd);
+    Some(((a1 + n1 * k).rem_euclid(lcm), lcm))
+}
+
+/// Finds the first step at which every ghost is on an end node at the same
+/// time, or `None` if they never line up or there are no ghosts at all.
+pub fn align(cycles: &[GhostCycle]) -> Option<u128> {
+    if cycles.is_empty() {
+        return None;
+    }
+
+    // If they line up before every ghost is in its loop, then it must be at
+    // one of the steps that some ghost is on an end node before its loop.
+    let mut early: Vec<usize> = cycles
+        .iter()
+        .flat_map(|cycle| cycle.prefix_hits.iter().copied())
+        .collect();
+    early.sort_unstable();
+    if let Some(step) = early
+        .into_iter()
+        .find(|step| cycles.iter().all(|cycle| cycle.hits_at(*step)))
+    {
+        return Some(step as u128);
+    }
+
+    // After that, each ghost could be at any of its hits in the loop, so each
+    // combination of them gives a set of congruences to solve.
+    let mut combined: Vec<(i128, i128)> = vec![(0, 1)];
+    for cycle in cycles {
+        combined = combined
             .iter()
-            .enumerate()
-            .filter(|node| node.1.chars().last().unwrap() == 'A')
-            .map(|node| node.0)
+            .flat_map(|&congruence| {
+                cycle.cycle_hits.iter().filter_map(move |&hit| {
+                    crt_pair(congruence, (hit as i128, cycle.period as i128))
+                })
+            })
             .collect();
-        let ends: Vec<usize> = nodes
+        combined.sort_unstable();
+        combined.dedup();
+    }
+
+    let all_looping = cycles.iter().map(|cycle| cycle.offset).max().unwrap_or(0) as i128;
+    combined
+        .into_iter()
+        .map(|(step, modulus)| {
+            // The first matching step once every ghost is in its loop.
+            let behind = (all_looping - step).max(0);
+            step + (behind + modulus - 1) / modulus * modulus
+        })
+        .min()
+        .map(|step| step as u128)
+}
+
+/// The map of nodes, with each node given an ID by its position in the
+/// input.
+pub struct Network {
+    instructions: Vec<u8>,
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    edges: Vec<(usize, usize)>,
+}
+
+impl Network {
+    pub fn parse(input: &str) -> Result<Network, ParseError> {
+        let RawNetwork {
+            instructions,
+            nodes,
+            edges,
+        } = parse_input(input)?;
+        let edges = match_edges_to_nodes(&nodes, &edges)?;
+        let ids = nodes
             .iter()
             .enumerate()
-            .filter(|node| node.1.chars().last().unwrap() == 'Z')
-            .map(|node| node.0)
+            .map(|(id, node)| (node.clone(), id))
             .collect();
+        Ok(Network {
+            instructions: instructions.into_bytes(),
+            names: nodes,
+            ids,
+            edges,
+        })
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
 
-        let distances: Vec<usize> = starts
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.names.len()
+    }
+
+    /// The node that is reached by following the instruction for `step`.
+    pub fn step(&self, node: usize, step: usize) -> usize {
+        match self.instructions[step % self.instructions.len()] {
+            b'L' => self.edges[node].0,
+            _ => self.edges[node].1,
+        }
+    }
+
+    /// Walks from `start` until the ghost is back at a node that it has been
+    /// at before, at the same point in the instructions.
+    pub fn ghost_cycle(&self, start: usize, is_end: impl Fn(usize) -> bool) -> GhostCycle {
+        let len = self.instructions.len();
+        let mut first_seen: Vec<Option<usize>> = vec![None; self.node_count() * len];
+        let mut hits = vec![];
+        let (mut node, mut step) = (start, 0);
+        loop {
+            let state = node * len + step % len;
+            if let Some(offset) = first_seen[state] {
+                let (prefix_hits, cycle_hits) = hits.into_iter().partition(|hit| *hit < offset);
+                return GhostCycle {
+                    offset,
+                    period: step - offset,
+                    prefix_hits,
+                    cycle_hits,
+                };
+            }
+            first_seen[state] = Some(step);
+            if is_end(node) {
+                hits.push(step);
+            }
+            node = self.step(node, step);
+            step += 1;
+        }
+    }
+
+    /// Writes the network as a Graphviz graph, with the start nodes (ending
+    /// in `A`) in green and the end nodes (ending in `Z`) drawn with a double
+    /// outline. If `highlight_from` names a node, everything that can be
+    /// reached from it is coloured in.
+    pub fn to_dot(&self, highlight_from: Option<&str>) -> String {
+        let mut graph = Digraph::new("day8");
+        for name in self.names.iter() {
+            if name.ends_with('A') {
+                graph.node(name, &[("style", "filled"), ("fillcolor", "palegreen")]);
+            } else if name.ends_with('Z') {
+                graph.node(name, &[("shape", "doublecircle")]);
+            } else {
+                graph.node(name, &[]);
+            }
+        }
+        for (name, &(left, right)) in self.names.iter().zip(self.edges.iter()) {
+            if left == right {
+                graph.edge(name, self.name(left), &[("label", "L/R")]);
+            } else {
+                graph.edge(name, self.name(left), &[("label", "L")]);
+                graph.edge(name, self.name(right), &[("label", "R")]);
+            }
+        }
+        if let Some(start) = highlight_from {
+            graph.highlight_reachable(start);
+        }
+        graph.to_string()
+    }
+}
+
+pub struct Day8 {
+    network: Network,
+}
+
+impl Solution for Day8 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Ok(Day8 {
+            network: Network::parse(input)?,
+        })
+    }
+
+    fn part1(&self) -> Answer {
+        let (Some(start), Some(dest)) = (self.network.id("AAA"), self.network.id("ZZZ")) else {
+            return Answer::Unsolved;
+        };
+
+        self.network
+            .ghost_cycle(start, |node| node == dest)
+            .first_hit()
+            .map_or(Answer::Unsolved, Answer::from)
+    }
+
+    fn part2(&self) -> Answer {
+        let network = &self.network;
+        let ends_with = |id: usize, c: char| network.name(id).ends_with(c);
+        let starts: Vec<usize> = (0..network.node_count())
+            .filter(|id| ends_with(*id, 'A'))
+            .collect();
+
+        let cycles: Vec<GhostCycle> = starts
             .par_iter()
-            .map(|start| {
-                let mut pos = *start;
-                let mut i = 0;
-                while !ends.contains(&pos) {
-                    let instruction = instructions.chars().nth(i % instructions.len()).unwrap();
-                    pos = match instruction {
-                        'L' => matched_edges[pos].0,
-                        'R' => matched_edges[pos].1,
-                        _ => panic!("Unknown direction"),
-                    };
-                    i += 1;
-                }
-                i
-            })
+            .map(|start| network.ghost_cycle(*start, |node| ends_with(node, 'Z')))
             .collect();
 
-        let min_dist = distances.iter().fold(1, |accum, dist| lcm(accum, *dist));
+        // There are no ghosts, or they never all reach the end at once.
+        align(&cycles).map_or(Answer::Unsolved, Answer::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{answers::test_answer, input::test_input, solution::Part};
+
+    #[test]
+    fn day8_part1() {
+        let Some(input) = test_input(8) else {
+            return;
+        };
+
+        assert_eq!(
+            Day8::parse(&input).unwrap().part1(),
+            test_answer(8, Part::One)
+        );
+    }
+
+    #[test]
+    fn day8_part2() {
+        let Some(input) = test_input(8) else {
+            return;
+        };
+
+        assert_eq!(
+            Day8::parse(&input).unwrap().part2(),
+            test_answer(8, Part::Two)
+        );
+    }
+
+    #[test]
+    fn ghost_cycles() {
+        let network = Network::parse(include_str!("../../samples/day8/example3.txt")).unwrap();
+        let is_end = |node: usize| network.name(node).ends_with('Z');
+
+        let first = network.ghost_cycle(network.id("11A").unwrap(), is_end);
+        assert_eq!(
+            first,
+            GhostCycle {
+                offset: 1,
+                period: 2,
+                prefix_hits: vec![],
+                cycle_hits: vec![2]
+            }
+        );
+        let second = network.ghost_cycle(network.id("22A").unwrap(), is_end);
+        assert_eq!((second.offset, second.period), (1, 6));
+        assert_eq!(second.cycle_hits, vec![3, 6]);
+        assert_eq!(align(&[first, second]), Some(6));
+    }
+
+    #[test]
+    fn network_to_dot() {
+        let network = Network::parse(include_str!("../../samples/day8/example3.txt")).unwrap();
+        let dot = network.to_dot(Some("11A"));
+        assert!(dot.starts_with("digraph \"day8\" {\n"));
+        let start = "\"11A\" [style=\"filled\", fillcolor=\"palegreen\", color=\"red\"];";
+        assert!(dot.contains(start));
+        assert!(dot.contains("\"22Z\" [shape=\"doublecircle\"];"));
+        assert!(dot.contains("\"11B\" -> \"11Z\" [label=\"R\", color=\"red\"];"));
+        assert!(dot.contains("\"22B\" -> \"22C\" [label=\"L/R\"];"));
+    }
+
+    #[test]
+    fn align_without_lcm() {
+        let cycle = |offset, period, prefix_hits: &[usize], cycle_hits: &[usize]| GhostCycle {
+            offset,
+            period,
+            prefix_hits: prefix_hits.to_vec(),
+            cycle_hits: cycle_hits.to_vec(),
+        };
+
+        // 8 is 3 mod 5 and 2 mod 3.
+        assert_eq!(
+            align(&[cycle(2, 5, &[], &[3]), cycle(1, 3, &[], &[2])]),
+            Some(8)
+        );
+        // Both ghosts hit the end before they start looping.
+        assert_eq!(
+            align(&[cycle(4, 5, &[1], &[6]), cycle(3, 4, &[1, 2], &[5])]),
+            Some(1)
+        );
+        // One ghost is always on an even step and the other on an odd one.
+        assert_eq!(
+            align(&[cycle(0, 4, &[], &[1]), cycle(0, 6, &[], &[2])]),
+            None
+        );
+    }
+
+    #[test]
+    fn no_ghosts() {
+        assert_eq!(align(&[]), None);
 
-        assert_eq!(min_dist, 10151663816849);
+        let day = Day8::parse("LR\n\nBBB = (CCZ, BBB)\nCCZ = (BBB, CCZ)").unwrap();
+        assert_eq!(day.part2(), Answer::Unsolved);
     }
 }
@@ -1,26 +1,145 @@
-pub fn extrapolate_next(series: &Vec<i64>) -> i64 {
-    let diffs = series
-        .windows(2)
-        .map(|pair| pair[1] - pair[0])
-        .collect::<Vec<i64>>();
-
-    if diffs.iter().sum::<i64>() == 0 {
-        series.last().unwrap().clone()
-    } else {
-        extrapolate_next(&diffs) + series.last().unwrap()
+use crate::{
+    error::{parse_lines, parse_token, ParseError},
+    solution::{Answer, Solution},
+};
+use std::fmt;
+
+const DAY: u8 = 9;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExtrapolateError {
+    /// There are no values to extrapolate from.
+    Empty,
+    /// The differences never all became zero, so the values might not come
+    /// from a polynomial, and if they do there are too few to tell which one.
+    NotPolynomial,
+    /// A value doesn't fit in an `i128`.
+    Overflow,
+}
+
+impl fmt::Display for ExtrapolateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtrapolateError::Empty => write!(f, "there are no values"),
+            ExtrapolateError::NotPolynomial => {
+                write!(f, "the differences never reduce to all zeros")
+            }
+            ExtrapolateError::Overflow => write!(f, "the values are too large"),
+        }
     }
 }
 
-pub fn extrapolate_prev(series: &Vec<i64>) -> i64 {
-    let diffs = series
-        .windows(2)
-        .map(|pair| pair[1] - pair[0])
-        .collect::<Vec<i64>>();
+impl std::error::Error for ExtrapolateError {}
+
+/// The polynomial that a sequence of values was taken from, stored as the
+/// first value in each row of differences. The value at any position `n` is
+/// then the sum of `diffs[k] * C(n, k)`, Newton's forward difference formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    diffs: Vec<i128>,
+    /// How many values the polynomial was fitted to.
+    len: usize,
+}
+
+/// The binomial coefficient `C(n, k)`, which is also defined for negative `n`.
+fn binomial(n: i128, k: usize) -> Option<i128> {
+    // Each partial product is itself a binomial coefficient, so every
+    // division is exact.
+    (0..k as i128).try_fold(1i128, |c, i| Some(c.checked_mul(n - i)? / (i + 1)))
+}
+
+impl Polynomial {
+    /// Finds the polynomial with the lowest degree that gives `series`. The
+    /// differences have to reach a row of zeros to be sure of the degree, so
+    /// a polynomial of degree `d` needs at least `d + 2` values.
+    pub fn fit(series: &[i64]) -> Result<Polynomial, ExtrapolateError> {
+        if series.is_empty() {
+            return Err(ExtrapolateError::Empty);
+        }
+
+        let mut row: Vec<i128> = series.iter().map(|&value| value.into()).collect();
+        let mut diffs = vec![];
+        while row.iter().any(|&value| value != 0) {
+            diffs.push(row[0]);
+            row = row
+                .windows(2)
+                .map(|pair| pair[1].checked_sub(pair[0]))
+                .collect::<Option<Vec<i128>>>()
+                .ok_or(ExtrapolateError::Overflow)?;
+            if row.is_empty() {
+                return Err(ExtrapolateError::NotPolynomial);
+            }
+        }
+
+        Ok(Polynomial {
+            diffs,
+            len: series.len(),
+        })
+    }
+
+    /// The degree of the polynomial, where a sequence of zeros has degree 0.
+    pub fn degree(&self) -> usize {
+        self.diffs.len().saturating_sub(1)
+    }
+
+    /// The value at position `n`, where the first value fitted is at 0.
+    pub fn at(&self, n: i128) -> Result<i128, ExtrapolateError> {
+        self.diffs
+            .iter()
+            .enumerate()
+            .try_fold(0i128, |total, (k, diff)| {
+                total.checked_add(binomial(n, k)?.checked_mul(*diff)?)
+            })
+            .ok_or(ExtrapolateError::Overflow)
+    }
 
-    if diffs.iter().sum::<i64>() == 0 {
-        series.first().unwrap().clone()
-    } else {
-        series.first().unwrap() - extrapolate_prev(&diffs)
+    /// The value `steps` places after the last value fitted.
+    pub fn forwards(&self, steps: usize) -> Result<i128, ExtrapolateError> {
+        self.at(self.len as i128 - 1 + steps as i128)
+    }
+
+    /// The value `steps` places before the first value fitted.
+    pub fn backwards(&self, steps: usize) -> Result<i128, ExtrapolateError> {
+        self.at(-(steps as i128))
+    }
+}
+
+pub struct Day9 {
+    measurements: Vec<Polynomial>,
+}
+
+impl Day9 {
+    fn sum_extrapolated(
+        &self,
+        extrapolate: impl Fn(&Polynomial) -> Result<i128, ExtrapolateError>,
+    ) -> Answer {
+        self.measurements
+            .iter()
+            .try_fold(0i128, |total, polynomial| {
+                total.checked_add(extrapolate(polynomial).ok()?)
+            })
+            .map_or(Answer::Unsolved, Answer::from)
+    }
+}
+
+impl Solution for Day9 {
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let measurements = parse_lines(input, |line| {
+            let series = line
+                .split_whitespace()
+                .map(|s| parse_token(DAY, line, s))
+                .collect::<Result<Vec<i64>, ParseError>>()?;
+            Polynomial::fit(&series).map_err(|err| ParseError::new(DAY, err.to_string()))
+        })?;
+        Ok(Day9 { measurements })
+    }
+
+    fn part1(&self) -> Answer {
+        self.sum_extrapolated(|polynomial| polynomial.forwards(1))
+    }
+
+    fn part2(&self) -> Answer {
+        self.sum_extrapolated(|polynomial| polynomial.backwards(1))
     }
 }
 
@@ -28,37 +147,64 @@ pub fn extrapolate_prev(series: &Vec<i64>) -> i64 {
 mod tests {
 
     use super::*;
-    const INPUT: &str = include_str!("../../data/day9/data.txt");
+    use crate::{answers::test_answer, input::test_input, solution::Part};
 
     #[test]
     fn day9_part1() {
-        let measurements = INPUT
-            .lines()
-            .map(|line| {
-                line.split_whitespace()
-                    .map(|s| s.parse::<i64>().unwrap())
-                    .collect::<Vec<i64>>()
-            })
-            .collect::<Vec<Vec<i64>>>();
+        let Some(input) = test_input(9) else {
+            return;
+        };
 
-        let extrap_sum: i64 = measurements.iter().map(extrapolate_next).sum();
-
-        assert_eq!(extrap_sum, 1806615041);
+        assert_eq!(
+            Day9::parse(&input).unwrap().part1(),
+            test_answer(9, Part::One)
+        );
     }
 
     #[test]
     fn day9_part2() {
-        let measurements = INPUT
-            .lines()
-            .map(|line| {
-                line.split_whitespace()
-                    .map(|s| s.parse::<i64>().unwrap())
-                    .collect::<Vec<i64>>()
-            })
-            .collect::<Vec<Vec<i64>>>();
+        let Some(input) = test_input(9) else {
+            return;
+        };
+
+        assert_eq!(
+            Day9::parse(&input).unwrap().part2(),
+            test_answer(9, Part::Two)
+        );
+    }
+
+    #[test]
+    fn fit_finds_the_degree() {
+        let fit = |series: &[i64]| Polynomial::fit(series).map(|p| p.degree());
+        assert_eq!(fit(&[3, 3, 3]), Ok(0));
+        assert_eq!(fit(&[0, 0]), Ok(0));
+        assert_eq!(fit(&[10, 13, 16, 21, 30, 45]), Ok(3));
+        // The differences sum to zero without all being zero.
+        assert_eq!(fit(&[1, -1]), Err(ExtrapolateError::NotPolynomial));
+        assert_eq!(fit(&[1, 2, 1]), Err(ExtrapolateError::NotPolynomial));
+        assert_eq!(fit(&[7]), Err(ExtrapolateError::NotPolynomial));
+        assert_eq!(fit(&[]), Err(ExtrapolateError::Empty));
+
+        let err = Day9::parse("1 2 3\n1 -1").err().unwrap();
+        assert_eq!(err.line, 2);
+    }
+
+    #[test]
+    fn extrapolate_many_steps() {
+        let squares = Polynomial::fit(&[0, 1, 4, 9]).unwrap();
+        assert_eq!(squares.forwards(1), Ok(16));
+        assert_eq!(squares.forwards(6), Ok(81));
+        assert_eq!(squares.backwards(3), Ok(9));
+        assert_eq!(squares.at(1_000_000), Ok(1_000_000_000_000));
 
-        let extrap_sum: i64 = measurements.iter().map(extrapolate_prev).sum();
+        let example = Polynomial::fit(&[10, 13, 16, 21, 30, 45]).unwrap();
+        assert_eq!(example.forwards(1), Ok(68));
+        assert_eq!(example.backwards(1), Ok(5));
 
-        assert_eq!(extrap_sum, 1211);
+        // The extrapolated value doesn't fit in an `i64`.
+        let wide = Polynomial::fit(&[-i64::MAX, 0, i64::MAX]).unwrap();
+        assert_eq!(wide.degree(), 1);
+        assert_eq!(wide.forwards(1), Ok(2 * i64::MAX as i128));
+        assert_eq!(wide.at(i128::MAX), Err(ExtrapolateError::Overflow));
     }
 }
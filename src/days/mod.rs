@@ -1,3 +1,5 @@
+use crate::{error::ParseError, solution::Solution};
+
 pub mod day1;
 pub mod day10;
 pub mod day11;
@@ -22,3 +24,57 @@ pub mod day6;
 pub mod day7;
 pub mod day8;
 pub mod day9;
+
+/// Parses a day's input into a boxed solver.
+pub type Parser = fn(&str) -> Result<Box<dyn Solution>, ParseError>;
+
+/// An entry in the registry of days.
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: u8,
+    pub parse: Parser,
+}
+
+fn boxed<S: Solution + 'static>(input: &str) -> Result<Box<dyn Solution>, ParseError> {
+    Ok(Box::new(S::parse(input)?))
+}
+
+const fn entry<S: Solution + 'static>(number: u8) -> Day {
+    Day {
+        number,
+        parse: boxed::<S>,
+    }
+}
+
+/// All of the solved days, in order.
+pub const DAYS: [Day; 24] = [
+    entry::<day1::Day1>(1),
+    entry::<day2::Day2>(2),
+    entry::<day3::Day3>(3),
+    entry::<day4::Day4>(4),
+    entry::<day5::Day5>(5),
+    entry::<day6::Day6>(6),
+    entry::<day7::Day7>(7),
+    entry::<day8::Day8>(8),
+    entry::<day9::Day9>(9),
+    entry::<day10::Day10>(10),
+    entry::<day11::Day11>(11),
+    entry::<day12::Day12>(12),
+    entry::<day13::Day13>(13),
+    entry::<day14::Day14>(14),
+    entry::<day15::Day15>(15),
+    entry::<day16::Day16>(16),
+    entry::<day17::Day17>(17),
+    entry::<day18::Day18>(18),
+    entry::<day19::Day19>(19),
+    entry::<day20::Day20>(20),
+    entry::<day21::Day21>(21),
+    entry::<day22::Day22>(22),
+    entry::<day23::Day23>(23),
+    entry::<day24::Day24>(24),
+];
+
+/// Looks up a day in the registry by its number.
+pub fn get(number: u8) -> Option<Day> {
+    DAYS.iter().find(|day| day.number == number).copied()
+}
@@ -0,0 +1,154 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fmt,
+};
+
+/// The colour used to pick out part of a graph.
+pub const HIGHLIGHT: &str = "red";
+
+type Attrs = Vec<(String, String)>;
+
+/// A directed graph that can be written out in Graphviz's DOT language, e.g.
+/// to be drawn with `dot -Tsvg graph.dot > graph.svg`.
+#[derive(Debug, Clone, Default)]
+pub struct Digraph {
+    name: String,
+    nodes: Vec<(String, Attrs)>,
+    edges: Vec<(String, String, Attrs)>,
+}
+
+fn to_attrs(attrs: &[(&str, &str)]) -> Attrs {
+    attrs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+/// Quotes an ID or attribute value, so that any characters can be used.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn write_attrs(f: &mut fmt::Formatter<'_>, attrs: &Attrs) -> fmt::Result {
+    if attrs.is_empty() {
+        return Ok(());
+    }
+    let attrs: Vec<String> = attrs
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, quote(value)))
+        .collect();
+    write!(f, " [{}]", attrs.join(", "))
+}
+
+impl Digraph {
+    pub fn new(name: &str) -> Digraph {
+        Digraph {
+            name: name.to_string(),
+            ..Digraph::default()
+        }
+    }
+
+    pub fn node(&mut self, id: &str, attrs: &[(&str, &str)]) {
+        self.nodes.push((id.to_string(), to_attrs(attrs)));
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str, attrs: &[(&str, &str)]) {
+        self.edges
+            .push((from.to_string(), to.to_string(), to_attrs(attrs)));
+    }
+
+    /// Every node that can be reached by following the edges from `start`,
+    /// including `start` itself.
+    pub fn reachable_from(&self, start: &str) -> HashSet<String> {
+        let mut links: HashMap<&str, Vec<&str>> = HashMap::new();
+        for (from, to, _) in self.edges.iter() {
+            links.entry(from).or_default().push(to);
+        }
+
+        let mut reached = HashSet::from([start.to_string()]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            for next in links.get(node).into_iter().flatten() {
+                if reached.insert(next.to_string()) {
+                    queue.push_back(next);
+                }
+            }
+        }
+        reached
+    }
+
+    /// Colours in every node and edge that can be reached from `start`.
+    pub fn highlight_reachable(&mut self, start: &str) {
+        let reached = self.reachable_from(start);
+        let highlight = || ("color".to_string(), HIGHLIGHT.to_string());
+        for (id, attrs) in self.nodes.iter_mut() {
+            if reached.contains(id) {
+                attrs.push(highlight());
+            }
+        }
+        for (from, _, attrs) in self.edges.iter_mut() {
+            if reached.contains(from) {
+                attrs.push(highlight());
+            }
+        }
+    }
+}
+
+impl fmt::Display for Digraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "digraph {} {{", quote(&self.name))?;
+        for (id, attrs) in self.nodes.iter() {
+            write!(f, "    {}", quote(id))?;
+            write_attrs(f, attrs)?;
+            writeln!(f, ";")?;
+        }
+        for (from, to, attrs) in self.edges.iter() {
+            write!(f, "    {} -> {}", quote(from), quote(to))?;
+            write_attrs(f, attrs)?;
+            writeln!(f, ";")?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Digraph {
+        let mut graph = Digraph::new("example");
+        graph.node("a", &[("shape", "box")]);
+        graph.node("b", &[]);
+        graph.node("c \"quoted\"", &[]);
+        graph.edge("a", "b", &[("label", "L")]);
+        graph.edge("b", "a", &[]);
+        graph.edge("c \"quoted\"", "a", &[]);
+        graph
+    }
+
+    #[test]
+    fn writes_dot() {
+        assert_eq!(
+            example().to_string(),
+            "digraph \"example\" {\n    \"a\" [shape=\"box\"];\n    \"b\";\n    \
+             \"c \\\"quoted\\\"\";\n    \"a\" -> \"b\" [label=\"L\"];\n    \"b\" -> \"a\";\n    \
+             \"c \\\"quoted\\\"\" -> \"a\";\n}\n"
+        );
+    }
+
+    #[test]
+    fn highlights_reachable_nodes() {
+        let mut graph = example();
+        assert_eq!(
+            graph.reachable_from("b"),
+            HashSet::from(["a".to_string(), "b".to_string()])
+        );
+
+        graph.highlight_reachable("b");
+        let dot = graph.to_string();
+        assert!(dot.contains("\"a\" [shape=\"box\", color=\"red\"];"));
+        assert!(dot.contains("\"c \\\"quoted\\\"\";"));
+        assert!(dot.contains("\"c \\\"quoted\\\"\" -> \"a\";"));
+        assert!(dot.contains("\"b\" -> \"a\" [color=\"red\"];"));
+    }
+}
@@ -0,0 +1,107 @@
+use std::{fmt, str::FromStr};
+
+/// An error found while parsing a day's puzzle input.
+///
+/// The line and column are both counted from 1, so that they match up with
+/// what an editor shows. A line or column of 0 means that the error isn't
+/// tied to a particular place in the input, e.g. a missing section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u8,
+    pub line: usize,
+    pub column: usize,
+    /// The text that could not be parsed.
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(day: u8, message: impl Into<String>) -> ParseError {
+        ParseError {
+            day,
+            line: 0,
+            column: 0,
+            text: String::new(),
+            message: message.into(),
+        }
+    }
+
+    /// Sets the line that the error was found on.
+    pub fn on_line(mut self, line: usize) -> ParseError {
+        self.line = line;
+        self
+    }
+
+    /// Points the error at `token`, which should be a slice of `line`.
+    pub fn at(mut self, line: &str, token: &str) -> ParseError {
+        self.column = column_of(line, token);
+        self.text = token.to_string();
+        self
+    }
+
+    /// Moves the column along by `offset` characters. This is used when the
+    /// error was found in a substring that didn't start at the beginning of
+    /// the line.
+    pub fn shift(mut self, offset: usize) -> ParseError {
+        if self.column > 0 {
+            self.column += offset;
+        }
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {}", self.day)?;
+        if self.line > 0 {
+            write!(f, ", line {}", self.line)?;
+        }
+        if self.column > 0 {
+            write!(f, ", column {}", self.column)?;
+        }
+        write!(f, ": {}", self.message)?;
+        if !self.text.is_empty() {
+            write!(f, " (found `{}`)", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Finds the column that `token` starts at within `line`, counting from 1.
+/// If `token` isn't a slice of `line` then it is searched for instead.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let start = line.as_ptr() as usize;
+    let offset = (token.as_ptr() as usize).wrapping_sub(start);
+    let byte_offset = if offset <= line.len() && line.is_char_boundary(offset) {
+        offset
+    } else {
+        match line.find(token) {
+            Some(offset) => offset,
+            None => return 0,
+        }
+    };
+    line[..byte_offset].chars().count() + 1
+}
+
+/// Parses `token`, which should be a slice of `line`, pointing the error at
+/// the token if it is not valid.
+pub fn parse_token<T: FromStr>(day: u8, line: &str, token: &str) -> Result<T, ParseError> {
+    token.trim().parse::<T>().map_err(|_| {
+        ParseError::new(day, format!("expected a {}", std::any::type_name::<T>())).at(line, token)
+    })
+}
+
+/// Parses each line of the input with `parse_line`, adding the line number
+/// to any errors.
+pub fn parse_lines<T>(
+    input: &str,
+    parse_line: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse_line(line).map_err(|err| err.on_line(i + 1)))
+        .collect()
+}
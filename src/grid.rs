@@ -0,0 +1,346 @@
+use crate::error::{parse_lines, ParseError};
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+/// A position in a grid, as `(row, column)`. The coordinates are signed so
+/// that stepping off the edge of the grid gives a position that is simply out
+/// of bounds, rather than an overflow.
+pub type Coord = (i64, i64);
+
+/// The steps to the 4 orthogonal neighbours, clockwise from north.
+pub const ORTHOGONAL: [Coord; 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// The steps to all 8 neighbours, clockwise from north.
+pub const ALL_DIRECTIONS: [Coord; 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// A rectangular grid, stored row by row in a single `Vec`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid from its rows, or returns `None` if they are not all the
+    /// same width.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Grid<T>> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Some(Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Parses a grid with one row per line, turning each character into a
+    /// tile with `tile`. Characters that `tile` rejects are reported as
+    /// "unexpected tile, expected `<expected>`".
+    pub fn parse_with(
+        day: u8,
+        input: &str,
+        expected: &str,
+        tile: impl Fn(char) -> Option<T>,
+    ) -> Result<Grid<T>, ParseError> {
+        let rows = parse_lines(input, |line| {
+            line.char_indices()
+                .map(|(offset, c)| {
+                    tile(c).ok_or_else(|| {
+                        ParseError::new(day, format!("unexpected tile, expected {}", expected))
+                            .at(line, &line[offset..offset + c.len_utf8()])
+                    })
+                })
+                .collect::<Result<Vec<T>, ParseError>>()
+        })?;
+
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some(i) = rows.iter().position(|row| row.len() != width) {
+            return Err(ParseError::new(
+                day,
+                format!(
+                    "the grid is not rectangular, this row is {} tiles wide but the first is {}",
+                    rows[i].len(),
+                    width
+                ),
+            )
+            .on_line(i + 1));
+        }
+        Ok(Grid::from_rows(rows).expect("the rows have already been checked"))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn in_bounds(&self, pos: Coord) -> bool {
+        pos.0 >= 0 && pos.1 >= 0 && (pos.0 as usize) < self.height && (pos.1 as usize) < self.width
+    }
+
+    fn index_of(&self, pos: Coord) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| pos.0 as usize * self.width + pos.1 as usize)
+    }
+
+    /// Gets the tile at `pos`, or `None` if it is off the grid.
+    pub fn get(&self, pos: Coord) -> Option<&T> {
+        self.index_of(pos).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, pos: Coord) -> Option<&mut T> {
+        self.index_of(pos).map(|index| &mut self.cells[index])
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.cells[i * self.width..(i + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` doesn't accept a size of 0, but then there are no cells.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn coords(&self) -> impl Iterator<Item = Coord> {
+        let width = self.width as i64;
+        (0..self.height as i64).flat_map(move |i| (0..width).map(move |j| (i, j)))
+    }
+
+    /// Every tile in the grid along with its position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Coord, &T)> {
+        self.coords().zip(self.cells.iter())
+    }
+
+    /// The tiles that are one step from `pos` in each of `steps`, skipping
+    /// any that are off the grid.
+    pub fn neighbours<'a>(
+        &'a self,
+        pos: Coord,
+        steps: &'a [Coord],
+    ) -> impl Iterator<Item = (Coord, &'a T)> + 'a {
+        steps.iter().filter_map(move |step| {
+            let next = (pos.0 + step.0, pos.1 + step.1);
+            self.get(next).map(|tile| (next, tile))
+        })
+    }
+
+    /// The orthogonal neighbours of `pos` that are on the grid.
+    pub fn neighbours4(&self, pos: Coord) -> impl Iterator<Item = (Coord, &T)> {
+        self.neighbours(pos, &ORTHOGONAL)
+    }
+
+    /// The orthogonal and diagonal neighbours of `pos` that are on the grid.
+    pub fn neighbours8(&self, pos: Coord) -> impl Iterator<Item = (Coord, &T)> {
+        self.neighbours(pos, &ALL_DIRECTIONS)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// Finds the first position of `value`, searching row by row.
+    pub fn find(&self, value: &T) -> Option<Coord> {
+        self.iter()
+            .find(|(_, tile)| *tile == value)
+            .map(|(pos, _)| pos)
+    }
+
+    /// Finds every position of `value`, row by row.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Coord> + 'a {
+        self.iter()
+            .filter(move |(_, tile)| *tile == value)
+            .map(|(pos, _)| pos)
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    /// Makes a grid with every tile set to `fill`.
+    pub fn new(height: usize, width: usize, fill: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+
+    pub fn column(&self, j: usize) -> Vec<T> {
+        self.rows().map(|row| row[j].clone()).collect()
+    }
+
+    /// Builds a `height` by `width` grid, taking each tile `(i, j)` from the
+    /// cell at index `source(i, j)` of this grid.
+    fn rearrange(
+        &self,
+        height: usize,
+        width: usize,
+        source: impl Fn(usize, usize) -> usize,
+    ) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: (0..height)
+                .flat_map(|i| (0..width).map(move |j| (i, j)))
+                .map(|(i, j)| self.cells[source(i, j)].clone())
+                .collect(),
+        }
+    }
+
+    /// Swaps the rows and columns.
+    pub fn transpose(&self) -> Grid<T> {
+        self.rearrange(self.width, self.height, |i, j| j * self.width + i)
+    }
+
+    /// Rotates the grid a quarter turn clockwise, so that the first column
+    /// becomes the first row.
+    pub fn rotate_cw(&self) -> Grid<T> {
+        self.rearrange(self.width, self.height, |i, j| {
+            (self.height - 1 - j) * self.width + i
+        })
+    }
+
+    /// Rotates the grid a quarter turn anticlockwise, so that the first row
+    /// becomes the first column.
+    pub fn rotate_ccw(&self) -> Grid<T> {
+        self.rearrange(self.width, self.height, |i, j| {
+            j * self.width + (self.width - 1 - i)
+        })
+    }
+}
+
+impl Grid<char> {
+    /// Parses a grid of characters, checking that every tile is one of
+    /// `allowed`.
+    pub fn parse_chars(day: u8, input: &str, allowed: &str) -> Result<Grid<char>, ParseError> {
+        Grid::parse_with(day, input, &format!("one of `{}`", allowed), |c| {
+            allowed.contains(c).then_some(c)
+        })
+    }
+}
+
+impl<T> Index<Coord> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Coord) -> &T {
+        match self.index_of(pos) {
+            Some(index) => &self.cells[index],
+            None => panic!(
+                "{:?} is outside of the {}x{} grid",
+                pos, self.height, self.width
+            ),
+        }
+    }
+}
+
+impl<T> IndexMut<Coord> for Grid<T> {
+    fn index_mut(&mut self, pos: Coord) -> &mut T {
+        match self.index_of(pos) {
+            Some(index) => &mut self.cells[index],
+            None => panic!(
+                "{:?} is outside of the {}x{} grid",
+                pos, self.height, self.width
+            ),
+        }
+    }
+}
+
+/// Writes the grid one row per line, which gives back the puzzle input for a
+/// grid of characters.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for tile in row {
+                write!(f, "{}", tile)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<char> {
+        Grid::parse_chars(0, "abc\ndef\n", "abcdef").unwrap()
+    }
+
+    #[test]
+    fn get_is_none_off_the_grid() {
+        let grid = example();
+        assert_eq!((grid.height(), grid.width()), (2, 3));
+        assert_eq!(grid.get((1, 2)), Some(&'f'));
+        assert_eq!(grid[(0, 1)], 'b');
+        assert_eq!(grid.get((-1, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        assert_eq!(grid.get((2, 0)), None);
+    }
+
+    #[test]
+    fn neighbours_skip_tiles_off_the_grid() {
+        let grid = example();
+        let corner: Vec<char> = grid.neighbours4((0, 0)).map(|(_, c)| *c).collect();
+        assert_eq!(corner, vec!['b', 'd']);
+        let middle: Vec<Coord> = grid.neighbours8((0, 1)).map(|(pos, _)| pos).collect();
+        assert_eq!(middle, vec![(0, 2), (1, 2), (1, 1), (1, 0), (0, 0)]);
+    }
+
+    #[test]
+    fn transpose_and_rotate() {
+        let grid = example();
+        assert_eq!(grid.transpose().to_string(), "ad\nbe\ncf");
+        assert_eq!(grid.rotate_cw().to_string(), "da\neb\nfc");
+        assert_eq!(grid.rotate_ccw().to_string(), "cf\nbe\nad");
+        assert_eq!(grid.rotate_cw().rotate_ccw(), grid);
+        assert_eq!(grid.column(1), vec!['b', 'e']);
+    }
+
+    #[test]
+    fn find_tiles() {
+        let grid = Grid::parse_chars(0, ".S.\nS..", ".S").unwrap();
+        assert_eq!(grid.find(&'S'), Some((0, 1)));
+        assert_eq!(
+            grid.find_all(&'S').collect::<Vec<_>>(),
+            vec![(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.find(&'#'), None);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = Grid::parse_chars(3, "..\n.x", ".#").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (2, 2, "x"));
+
+        let err = Grid::parse_chars(3, "..\n...", ".#").unwrap_err();
+        assert_eq!((err.line, err.column), (2, 0));
+
+        let digits = Grid::parse_with(3, "12\n34", "a digit", |c| c.to_digit(10)).unwrap();
+        assert_eq!(digits.map(|d| d * 2).to_string(), "24\n68");
+    }
+}
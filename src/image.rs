@@ -0,0 +1,82 @@
+use crate::grid::Grid;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+};
+
+/// A pixel's red, green and blue values.
+pub type Rgb = [u8; 3];
+
+fn pixel_bytes(image: &Grid<Rgb>) -> Vec<u8> {
+    image.iter().flat_map(|(_, pixel)| *pixel).collect()
+}
+
+/// Writes the image as a binary PPM, which is simple enough to need no
+/// encoder but is not as widely supported as PNG.
+pub fn write_ppm(image: &Grid<Rgb>, mut out: impl Write) -> io::Result<()> {
+    write!(out, "P6\n{} {}\n255\n", image.width(), image.height())?;
+    out.write_all(&pixel_bytes(image))
+}
+
+pub fn write_png(image: &Grid<Rgb>, out: impl Write) -> io::Result<()> {
+    let mut encoder = png::Encoder::new(out, image.width() as u32, image.height() as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&pixel_bytes(image))?;
+    Ok(writer.finish()?)
+}
+
+/// Writes the image to `path`, as a PNG or a PPM depending on its extension.
+pub fn save(image: &Grid<Rgb>, path: &Path) -> io::Result<()> {
+    let extension = path.extension().and_then(|ext| ext.to_str());
+    let write = match extension.map(str::to_ascii_lowercase).as_deref() {
+        Some("png") => write_png,
+        Some("ppm") => write_ppm,
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "images can only be saved as .png or .ppm",
+            ))
+        }
+    };
+    let mut out = BufWriter::new(File::create(path)?);
+    write(image, &mut out)?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example() -> Grid<Rgb> {
+        Grid::from_rows(vec![
+            vec![[255, 0, 0], [0, 255, 0]],
+            vec![[0, 0, 255], [1, 2, 3]],
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn ppm() {
+        let mut out = vec![];
+        write_ppm(&example(), &mut out).unwrap();
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend([255, 0, 0, 0, 255, 0, 0, 0, 255, 1, 2, 3]);
+        assert_eq!(out, expected);
+    }
+
+    #[test]
+    fn png() {
+        let mut out = vec![];
+        write_png(&example(), &mut out).unwrap();
+        assert!(out.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        let decoder = png::Decoder::new(out.as_slice());
+        let mut reader = decoder.read_info().unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut pixels).unwrap();
+        assert_eq!(pixels, pixel_bytes(&example()));
+    }
+}
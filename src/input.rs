@@ -0,0 +1,185 @@
+use std::{
+    env, fmt,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// The environment variable that sets the directory holding the puzzle inputs.
+pub const INPUT_DIR_VAR: &str = "AOC2023_INPUT_DIR";
+
+/// The directory the puzzle inputs are read from if no other is configured.
+pub const DEFAULT_INPUT_DIR: &str = "data";
+
+/// The directory holding the worked examples from the puzzles.
+pub const SAMPLES_DIR: &str = "samples";
+
+/// The input set holding each day's real puzzle input.
+pub const PUZZLE_SET: &str = "input";
+
+/// Input sets whose names start with this are worked examples, which are kept
+/// in `samples` rather than the input directory.
+pub const EXAMPLE_SET_PREFIX: &str = "example";
+
+/// The file names that are searched for in each day's directory, in order.
+/// Older checkouts used `data.txt`, so that is still picked up.
+const INPUT_FILE_NAMES: [&str; 2] = ["input.txt", "data.txt"];
+
+/// The name of the environment variable that points at the input file for a
+/// single day, e.g. `AOC2023_DAY17_INPUT`.
+pub fn day_input_var(day: u8) -> String {
+    format!("AOC2023_DAY{}_INPUT", day)
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    /// None of the places that the input could be were found.
+    Missing { day: u8, searched: Vec<PathBuf> },
+    /// The input was found, but could not be read.
+    Io { path: PathBuf, source: io::Error },
+    /// The input could not be read from stdin.
+    Stdin(io::Error),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Missing { day, searched } => {
+                write!(f, "No input found for day {}. Looked in: ", day)?;
+                let paths: Vec<String> = searched
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect();
+                write!(
+                    f,
+                    "{}. Set {} or {} to point somewhere else.",
+                    paths.join(", "),
+                    day_input_var(*day),
+                    INPUT_DIR_VAR
+                )
+            }
+            InputError::Io { path, source } => {
+                write!(f, "Unable to read {}: {}", path.display(), source)
+            }
+            InputError::Stdin(source) => write!(f, "Unable to read stdin: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Where a day's puzzle input is read from.
+#[derive(Debug, Clone)]
+pub enum InputSource {
+    /// A directory holding a `dayN/input.txt` file for each day.
+    Dir(PathBuf),
+    /// A single input file.
+    File(PathBuf),
+    /// The input is piped in on stdin.
+    Stdin,
+    /// The input is already in memory.
+    Text(String),
+}
+
+impl InputSource {
+    /// Works out where to read a day's input from using the environment.
+    /// A per-day file takes precedence over the input directory, which falls
+    /// back to `data` if it is not set.
+    pub fn from_env(day: u8) -> InputSource {
+        if let Some(path) = env::var_os(day_input_var(day)) {
+            return InputSource::File(PathBuf::from(path));
+        }
+
+        InputSource::Dir(input_dir_from_env())
+    }
+
+    /// Works out where to read a day's input for a named input set, which is
+    /// kept in a `dayN/<set>.txt` file. The examples are read from `samples`,
+    /// and every other set from `input_dir`, or the environment if that is not
+    /// given.
+    pub fn for_set(set: &str, day: u8, input_dir: Option<&Path>) -> InputSource {
+        if set.starts_with(EXAMPLE_SET_PREFIX) {
+            return InputSource::File(set_file(Path::new(SAMPLES_DIR), set, day));
+        }
+
+        match (set, input_dir) {
+            (PUZZLE_SET, Some(dir)) => InputSource::Dir(dir.to_path_buf()),
+            (PUZZLE_SET, None) => InputSource::from_env(day),
+            (_, Some(dir)) => InputSource::File(set_file(dir, set, day)),
+            (_, None) => InputSource::File(set_file(&input_dir_from_env(), set, day)),
+        }
+    }
+
+    pub fn load(&self, day: u8) -> Result<String, InputError> {
+        match self {
+            InputSource::Dir(dir) => {
+                let candidates: Vec<PathBuf> = INPUT_FILE_NAMES
+                    .iter()
+                    .map(|name| dir.join(format!("day{}", day)).join(name))
+                    .collect();
+                match candidates.iter().find(|path| path.is_file()) {
+                    Some(path) => read_file(path),
+                    None => Err(InputError::Missing {
+                        day,
+                        searched: candidates,
+                    }),
+                }
+            }
+            InputSource::File(path) => {
+                if path.is_file() {
+                    read_file(path)
+                } else {
+                    Err(InputError::Missing {
+                        day,
+                        searched: vec![path.clone()],
+                    })
+                }
+            }
+            InputSource::Stdin => {
+                let mut input = String::new();
+                io::stdin()
+                    .read_to_string(&mut input)
+                    .map_err(InputError::Stdin)?;
+                Ok(input)
+            }
+            InputSource::Text(text) => Ok(text.clone()),
+        }
+    }
+}
+
+fn input_dir_from_env() -> PathBuf {
+    match env::var_os(INPUT_DIR_VAR) {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(DEFAULT_INPUT_DIR),
+    }
+}
+
+fn set_file(dir: &Path, set: &str, day: u8) -> PathBuf {
+    dir.join(format!("day{}", day)).join(format!("{}.txt", set))
+}
+
+fn read_file(path: &Path) -> Result<String, InputError> {
+    std::fs::read_to_string(path).map_err(|source| InputError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Loads a day's input from wherever the environment says it is.
+pub fn load(day: u8) -> Result<String, InputError> {
+    InputSource::from_env(day).load(day)
+}
+
+/// Loads a day's input for the tests. The puzzle inputs are private, so if
+/// the input can't be found the test is skipped rather than failed, and the
+/// examples in `samples` are relied on instead.
+#[cfg(test)]
+pub(crate) fn test_input(day: u8) -> Option<String> {
+    match load(day) {
+        Ok(input) => Some(input),
+        Err(InputError::Missing { .. }) => {
+            eprintln!("Skipping, no input for day {}.", day);
+            None
+        }
+        Err(err) => panic!("{}", err),
+    }
+}
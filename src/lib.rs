@@ -1 +1,13 @@
+pub mod alloc;
+pub mod answers;
 pub mod days;
+pub mod dot;
+pub mod error;
+pub mod grid;
+pub mod image;
+pub mod input;
+pub mod report;
+pub mod runner;
+pub mod search;
+pub mod solution;
+pub mod verify;
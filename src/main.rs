@@ -0,0 +1,325 @@
+use aoc2023::{
+    alloc::CountingAllocator,
+    answers::{Manifest, MANIFEST_FILE},
+    days::{self, day10::PipeMap, Day},
+    image,
+    input::InputSource,
+    report::{input_hash, to_csv, to_json, ReportRow},
+    runner::{run_day, RunResult},
+    solution::Part,
+    verify::{verify, Check, Outcome},
+};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::{path::PathBuf, process::ExitCode, time::Duration};
+
+// Counts allocations so that the peak memory use of each day can be reported.
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Parser)]
+#[command(name = "aoc2023", about = "Runs the Advent of Code 2023 solutions.")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Runs the solver for a single day, or for all days.
+    Run(RunArgs),
+    /// Checks the solvers against the expected answers in the manifest.
+    Verify(VerifyArgs),
+    /// Draws a day's input, for the days that can be drawn.
+    Render(RenderArgs),
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// The day to run.
+    #[arg(long, required_unless_present = "all", conflicts_with = "all")]
+    day: Option<u8>,
+
+    /// Only run a single part of the day.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    part: Option<u8>,
+
+    /// The input file to run against.
+    #[arg(long, requires = "day", conflicts_with_all = ["input_dir", "stdin"])]
+    input: Option<PathBuf>,
+
+    /// The directory holding a `dayN/input.txt` file for each day. Defaults
+    /// to `$AOC2023_INPUT_DIR`, or `data` if that is not set.
+    #[arg(long, conflicts_with = "stdin")]
+    input_dir: Option<PathBuf>,
+
+    /// Read the input from stdin.
+    #[arg(long, requires = "day")]
+    stdin: bool,
+
+    /// Run every day.
+    #[arg(long)]
+    all: bool,
+
+    /// How to write out the results.
+    #[arg(long, value_enum, default_value_t = ReportFormat::Table)]
+    format: ReportFormat,
+
+    /// Write the results to a file, rather than stdout.
+    #[arg(long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ReportFormat {
+    /// A table for reading in the terminal.
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Args)]
+struct VerifyArgs {
+    /// The manifest of expected answers.
+    #[arg(long, default_value = MANIFEST_FILE)]
+    manifest: PathBuf,
+
+    /// Only check these input sets, e.g. `input` or `example`.
+    #[arg(long)]
+    set: Vec<String>,
+
+    /// Only check a single day.
+    #[arg(long)]
+    day: Option<u8>,
+
+    /// The directory holding the `dayN/<set>.txt` files for each input set.
+    /// Defaults to `$AOC2023_INPUT_DIR`, or `data` if that is not set.
+    #[arg(long)]
+    input_dir: Option<PathBuf>,
+}
+
+#[derive(Args)]
+struct RenderArgs {
+    /// The day to draw. Only day 10 can be drawn so far.
+    #[arg(long)]
+    day: u8,
+
+    /// The input file to draw. Defaults to the day's puzzle input.
+    #[arg(long)]
+    input: Option<PathBuf>,
+
+    /// Save an image to this `.png` or `.ppm` file, rather than drawing to
+    /// the terminal.
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// Draw to the terminal without colour.
+    #[arg(long)]
+    no_colour: bool,
+}
+
+impl RunArgs {
+    fn input_source(&self, day: u8) -> InputSource {
+        if let Some(path) = &self.input {
+            InputSource::File(path.clone())
+        } else if let Some(dir) = &self.input_dir {
+            InputSource::Dir(dir.clone())
+        } else if self.stdin {
+            InputSource::Stdin
+        } else {
+            InputSource::from_env(day)
+        }
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.1?}", duration)
+}
+
+fn print_header() {
+    println!(
+        "{:>4}  {:>4}  {:>20}  {:>10}  {:>10}",
+        "Day", "Part", "Answer", "Parse", "Solve"
+    );
+}
+
+fn print_row(result: &RunResult) {
+    let answer = match result.panic {
+        Some(_) => "panicked".to_string(),
+        None => result.answer.to_string(),
+    };
+    println!(
+        "{:>4}  {:>4}  {:>20}  {:>10}  {:>10}",
+        result.day,
+        result.part.number(),
+        answer,
+        format_duration(result.parse_time),
+        format_duration(result.solve_time)
+    );
+}
+
+fn run(args: RunArgs) -> ExitCode {
+    let parts: Vec<Part> = match args.part {
+        Some(number) => Part::from_number(number).into_iter().collect(),
+        None => Part::ALL.to_vec(),
+    };
+
+    let selected: Vec<Day> = match args.day {
+        Some(number) => match days::get(number) {
+            Some(day) => vec![day],
+            None => {
+                eprintln!("Day {} has not been solved.", number);
+                return ExitCode::FAILURE;
+            }
+        },
+        None => days::DAYS.to_vec(),
+    };
+
+    let table = args.format == ReportFormat::Table;
+    if table && args.output.is_some() {
+        eprintln!("Only json or csv reports can be written to a file.");
+        return ExitCode::FAILURE;
+    }
+    let mut status = ExitCode::SUCCESS;
+    let mut rows = vec![];
+    if table {
+        print_header();
+    }
+    for day in selected.iter() {
+        let input = match args.input_source(day.number).load(day.number) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("{}", err);
+                status = ExitCode::FAILURE;
+                continue;
+            }
+        };
+
+        match run_day(day, &input, &parts) {
+            Ok(results) => {
+                if results.iter().any(|result| result.panic.is_some()) {
+                    status = ExitCode::FAILURE;
+                }
+                if table {
+                    results.iter().for_each(print_row);
+                } else {
+                    let hash = input_hash(&input);
+                    rows.extend(results.iter().map(|result| ReportRow::new(result, hash)));
+                }
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                status = ExitCode::FAILURE;
+            }
+        }
+    }
+
+    let report = match args.format {
+        // The table has already been printed as each day was run.
+        ReportFormat::Table => return status,
+        ReportFormat::Json => to_json(&rows) + "\n",
+        ReportFormat::Csv => to_csv(&rows),
+    };
+    match &args.output {
+        Some(path) => {
+            if let Err(err) = std::fs::write(path, report) {
+                eprintln!("Unable to write {}: {}", path.display(), err);
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{}", report),
+    }
+
+    status
+}
+
+fn print_check(check: &Check) {
+    println!(
+        "{:>4}  {:>4}  {:<10}  {}",
+        check.day,
+        check.part.number(),
+        check.set,
+        check.outcome
+    );
+}
+
+fn verify_answers(args: VerifyArgs) -> ExitCode {
+    let manifest = match Manifest::load(&args.manifest) {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let checks = verify(
+        &manifest,
+        |exp| {
+            (args.set.is_empty() || args.set.contains(&exp.set))
+                && args.day.is_none_or(|day| day == exp.day)
+        },
+        |set, day| InputSource::for_set(set, day, args.input_dir.as_deref()),
+    );
+
+    println!("{:>4}  {:>4}  {:<10}  Result", "Day", "Part", "Set");
+    checks.iter().for_each(print_check);
+
+    let count = |pred: fn(&Outcome) -> bool| checks.iter().filter(|c| pred(&c.outcome)).count();
+    let failed = count(Outcome::is_failure);
+    println!(
+        "\n{} passed, {} failed, {} missing",
+        count(|outcome| *outcome == Outcome::Pass),
+        failed,
+        count(|outcome| matches!(outcome, Outcome::Missing(_)))
+    );
+
+    if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn render(args: RenderArgs) -> ExitCode {
+    if args.day != 10 {
+        eprintln!("Day {} can't be drawn, only day 10 can.", args.day);
+        return ExitCode::FAILURE;
+    }
+
+    let source = match &args.input {
+        Some(path) => InputSource::File(path.clone()),
+        None => InputSource::from_env(args.day),
+    };
+    let pipe_map = match source.load(args.day) {
+        Ok(input) => match PipeMap::parse(&input) {
+            Ok(pipe_map) => pipe_map,
+            Err(err) => {
+                eprintln!("Unable to parse input: {}", err);
+                return ExitCode::FAILURE;
+            }
+        },
+        Err(err) => {
+            eprintln!("{}", err);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match &args.output {
+        Some(path) => {
+            if let Err(err) = image::save(&pipe_map.to_image(), path) {
+                eprintln!("Unable to write {}: {}", path.display(), err);
+                return ExitCode::FAILURE;
+            }
+        }
+        None => print!("{}", pipe_map.render(!args.no_colour)),
+    }
+    ExitCode::SUCCESS
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Run(args) => run(args),
+        Command::Verify(args) => verify_answers(args),
+        Command::Render(args) => render(args),
+    }
+}
@@ -0,0 +1,145 @@
+use crate::{runner::RunResult, solution::Answer};
+use serde::Serialize;
+
+/// Hashes a puzzle input with 64-bit FNV-1a, so that reports from different
+/// inputs can be told apart without including the inputs themselves.
+pub fn input_hash(input: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    input.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+/// One row of a run report, for a single part of a day.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportRow {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub parse_time_ns: u64,
+    pub solve_time_ns: u64,
+    pub peak_alloc_bytes: Option<usize>,
+    /// The FNV-1a hash of the input, as 16 hex digits.
+    pub input_hash: String,
+    /// The panic message, if the solver panicked.
+    pub panic: Option<String>,
+}
+
+impl ReportRow {
+    pub fn new(result: &RunResult, input_hash: u64) -> ReportRow {
+        ReportRow {
+            day: result.day,
+            part: result.part.number(),
+            answer: result.answer.clone(),
+            parse_time_ns: result.parse_time.as_nanos() as u64,
+            solve_time_ns: result.solve_time.as_nanos() as u64,
+            peak_alloc_bytes: result.peak_alloc,
+            input_hash: format!("{:016x}", input_hash),
+            panic: result.panic.clone(),
+        }
+    }
+}
+
+const CSV_HEADER: &str =
+    "day,part,answer,parse_time_ns,solve_time_ns,peak_alloc_bytes,input_hash,panic";
+
+/// Quotes a CSV field if it has anything in it that would need escaping.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+pub fn to_json(rows: &[ReportRow]) -> String {
+    serde_json::to_string_pretty(rows).expect("Report rows always serialise")
+}
+
+/// Writes the rows out as CSV, with a header. Unsolved answers, unknown peak
+/// allocations and the panic messages of solvers that didn't panic are left
+/// empty.
+pub fn to_csv(rows: &[ReportRow]) -> String {
+    let mut csv = String::from(CSV_HEADER);
+    csv.push('\n');
+    for row in rows {
+        let answer = match &row.answer {
+            Answer::Unsolved => String::new(),
+            answer => answer.to_string(),
+        };
+        let peak = row
+            .peak_alloc_bytes
+            .map_or(String::new(), |peak| peak.to_string());
+        let panic = row.panic.as_deref().unwrap_or_default();
+        csv.push_str(&format!(
+            "{},{},{},{},{},{},{},{}\n",
+            row.day,
+            row.part,
+            csv_field(&answer),
+            row.parse_time_ns,
+            row.solve_time_ns,
+            peak,
+            row.input_hash,
+            csv_field(panic)
+        ));
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(answer: Answer) -> ReportRow {
+        ReportRow {
+            day: 7,
+            part: 2,
+            answer,
+            parse_time_ns: 1500,
+            solve_time_ns: 42,
+            peak_alloc_bytes: None,
+            input_hash: format!("{:016x}", input_hash("")),
+            panic: None,
+        }
+    }
+
+    #[test]
+    fn fnv1a_hash() {
+        assert_eq!(input_hash(""), 0xcbf29ce484222325);
+        assert_eq!(input_hash("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn csv_report() {
+        let panicked = ReportRow {
+            panic: Some("index out of bounds".to_string()),
+            ..row(Answer::Unsolved)
+        };
+        let csv = to_csv(&[
+            row(Answer::from(5905)),
+            row(Answer::from("a,\"b\"")),
+            panicked,
+        ]);
+
+        assert_eq!(
+            csv.lines().collect::<Vec<&str>>(),
+            vec![
+                CSV_HEADER,
+                "7,2,5905,1500,42,,cbf29ce484222325,",
+                "7,2,\"a,\"\"b\"\"\",1500,42,,cbf29ce484222325,",
+                "7,2,,1500,42,,cbf29ce484222325,index out of bounds",
+            ]
+        );
+    }
+
+    #[test]
+    fn json_report() {
+        let json = to_json(&[row(Answer::Unsolved)]);
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(value[0]["answer"], serde_json::Value::Null);
+        assert_eq!(value[0]["parse_time_ns"], 1500);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    alloc,
+    days::Day,
+    error::ParseError,
+    solution::{Answer, Part},
+};
+use std::{
+    any::Any,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+/// The outcome of running one part of a day's solver.
+#[derive(Debug, Clone)]
+pub struct RunResult {
+    pub day: u8,
+    pub part: Part,
+    pub answer: Answer,
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+    /// The most heap memory in use at once while parsing the input and then
+    /// solving the part, on top of what was already in use. This is only
+    /// known if the counting allocator is installed.
+    pub peak_alloc: Option<usize>,
+    /// The panic message, if the solver panicked. The answer is then
+    /// unsolved.
+    pub panic: Option<String>,
+}
+
+/// Why a day's input could not be turned into a solver.
+#[derive(Debug, Clone)]
+pub enum RunError {
+    Parse(ParseError),
+    /// The parser panicked, with this message.
+    Panic(String),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RunError::Parse(err) => write!(f, "Unable to parse input: {}", err),
+            RunError::Panic(message) => write!(f, "Parsing the input panicked: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for RunError {}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Runs `f`, turning a panic into its message so that one broken solver
+/// doesn't stop the rest from running.
+fn catch_panic<T>(f: impl FnOnce() -> T) -> Result<T, String> {
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(panic_message)
+}
+
+/// Parses the input for a day, then runs the requested parts against it.
+/// The input is only parsed once, so each result reports the same parse time.
+/// A part that panics gives a result without an answer, and the other parts
+/// still run.
+pub fn run_day(day: &Day, input: &str, parts: &[Part]) -> Result<Vec<RunResult>, RunError> {
+    let baseline = alloc::reset_peak();
+    let start = Instant::now();
+    let solution = catch_panic(|| (day.parse)(input))
+        .map_err(RunError::Panic)?
+        .map_err(RunError::Parse)?;
+    let parse_time = start.elapsed();
+    let parse_peak = alloc::peak().saturating_sub(baseline);
+    let parsed_size = alloc::current().saturating_sub(baseline);
+
+    Ok(parts
+        .iter()
+        .map(|&part| {
+            let before = alloc::reset_peak();
+            let start = Instant::now();
+            let (answer, panic) = match catch_panic(|| solution.solve(part)) {
+                Ok(answer) => (answer, None),
+                Err(message) => (Answer::Unsolved, Some(message)),
+            };
+            let solve_time = start.elapsed();
+            let solve_peak = parsed_size + alloc::peak().saturating_sub(before);
+            RunResult {
+                day: day.number,
+                part,
+                answer,
+                parse_time,
+                solve_time,
+                peak_alloc: alloc::is_active().then_some(parse_peak.max(solve_peak)),
+                panic,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Solution;
+
+    struct Fragile;
+
+    impl Solution for Fragile {
+        fn parse(input: &str) -> Result<Self, ParseError> {
+            match input {
+                "panic" => panic!("bad input"),
+                "error" => Err(ParseError::new(0, "bad input")),
+                _ => Ok(Fragile),
+            }
+        }
+
+        fn part1(&self) -> Answer {
+            panic!("part {} is broken", 1)
+        }
+
+        fn part2(&self) -> Answer {
+            Answer::from(2)
+        }
+    }
+
+    const FRAGILE: Day = Day {
+        number: 0,
+        parse: |input| Ok(Box::new(Fragile::parse(input)?)),
+    };
+
+    #[test]
+    fn panics_are_caught() {
+        let results = run_day(&FRAGILE, "", &Part::ALL).unwrap();
+        assert_eq!(results[0].answer, Answer::Unsolved);
+        assert_eq!(results[0].panic.as_deref(), Some("part 1 is broken"));
+        assert_eq!(results[1].answer, Answer::from(2));
+        assert_eq!(results[1].panic, None);
+
+        assert!(matches!(
+            run_day(&FRAGILE, "panic", &Part::ALL),
+            Err(RunError::Panic(message)) if message == "bad input"
+        ));
+        assert!(matches!(
+            run_day(&FRAGILE, "error", &Part::ALL),
+            Err(RunError::Parse(_))
+        ));
+    }
+}
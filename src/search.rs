@@ -0,0 +1,171 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+    hash::Hash,
+};
+
+/// A problem that can be searched for the cheapest route between states.
+pub trait Successors {
+    type State: Clone + Eq + Hash;
+
+    /// The states that are one step on from `state`, along with the cost of
+    /// taking each step.
+    fn successors(&self, state: &Self::State) -> Vec<(Self::State, u64)>;
+}
+
+/// The cheapest route found by a search, from the start state to the goal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Route<S> {
+    pub cost: u64,
+    /// Every state along the route, including the start and the goal.
+    pub path: Vec<S>,
+}
+
+/// Finds the cheapest route from `start` to the first state that `is_goal`
+/// accepts, or `None` if there is no way to reach one.
+pub fn dijkstra<P: Successors>(
+    problem: &P,
+    start: P::State,
+    is_goal: impl FnMut(&P::State) -> bool,
+) -> Option<Route<P::State>> {
+    astar(problem, start, is_goal, |_| 0)
+}
+
+/// Finds the cheapest route in the same way as [`dijkstra`], but searching
+/// the states that `heuristic` says are closest to the goal first. The
+/// heuristic must never overestimate the remaining cost, or the route found
+/// might not be the cheapest.
+pub fn astar<P: Successors>(
+    problem: &P,
+    start: P::State,
+    mut is_goal: impl FnMut(&P::State) -> bool,
+    mut heuristic: impl FnMut(&P::State) -> u64,
+) -> Option<Route<P::State>> {
+    // Every state that has been reached, with the index of the state it was
+    // reached from, so that the path can be followed back to the start.
+    let mut reached: Vec<(P::State, Option<usize>)> = vec![(start.clone(), None)];
+    let mut best = HashMap::from([(start.clone(), 0)]);
+    let mut queue = BinaryHeap::from([Reverse((heuristic(&start), 0, 0))]);
+
+    while let Some(Reverse((_, cost, index))) = queue.pop() {
+        let state = reached[index].0.clone();
+        // The state has since been reached more cheaply.
+        if best[&state] < cost {
+            continue;
+        }
+
+        if is_goal(&state) {
+            return Some(Route {
+                cost,
+                path: follow_path(&reached, index),
+            });
+        }
+
+        for (next, step_cost) in problem.successors(&state) {
+            let next_cost = cost + step_cost;
+            if best.get(&next).is_some_and(|&known| known <= next_cost) {
+                continue;
+            }
+            best.insert(next.clone(), next_cost);
+            queue.push(Reverse((
+                next_cost + heuristic(&next),
+                next_cost,
+                reached.len(),
+            )));
+            reached.push((next, Some(index)));
+        }
+    }
+
+    None
+}
+
+fn follow_path<S: Clone>(reached: &[(S, Option<usize>)], end: usize) -> Vec<S> {
+    let mut path = vec![];
+    let mut index = Some(end);
+    while let Some(i) = index {
+        path.push(reached[i].0.clone());
+        index = reached[i].1;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Coord, Grid};
+
+    /// A graph given as the edges out of each node.
+    struct Edges(Vec<Vec<(usize, u64)>>);
+
+    impl Successors for Edges {
+        type State = usize;
+
+        fn successors(&self, state: &usize) -> Vec<(usize, u64)> {
+            self.0[*state].clone()
+        }
+    }
+
+    fn example() -> Edges {
+        Edges(vec![
+            vec![(1, 7), (2, 1)],
+            vec![(3, 1)],
+            vec![(1, 2), (3, 9)],
+            vec![],
+            vec![(0, 1)],
+        ])
+    }
+
+    #[test]
+    fn dijkstra_finds_the_cheapest_path() {
+        let route = dijkstra(&example(), 0, |node| *node == 3).unwrap();
+        assert_eq!(route.cost, 4);
+        assert_eq!(route.path, vec![0, 2, 1, 3]);
+    }
+
+    #[test]
+    fn start_can_be_the_goal() {
+        let route = dijkstra(&example(), 2, |node| *node == 2).unwrap();
+        assert_eq!(
+            route,
+            Route {
+                cost: 0,
+                path: vec![2]
+            }
+        );
+    }
+
+    #[test]
+    fn unreachable_goal() {
+        assert_eq!(dijkstra(&example(), 0, |node| *node == 4), None);
+    }
+
+    struct Maze(Grid<char>);
+
+    impl Successors for Maze {
+        type State = Coord;
+
+        fn successors(&self, state: &Coord) -> Vec<(Coord, u64)> {
+            self.0
+                .neighbours4(*state)
+                .filter(|(_, tile)| **tile == '.')
+                .map(|(pos, _)| (pos, 1))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn astar_agrees_with_dijkstra() {
+        let maze = Maze(Grid::parse_chars(0, "...#\n.#..\n.#.#\n...#", ".#").unwrap());
+        let goal = (2, 2);
+        let manhattan = |pos: &Coord| pos.0.abs_diff(goal.0) + pos.1.abs_diff(goal.1);
+
+        let route = astar(&maze, (0, 0), |pos| *pos == goal, manhattan).unwrap();
+        assert_eq!(route.cost, 4);
+        assert_eq!(route.path, vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)]);
+        assert_eq!(
+            dijkstra(&maze, (0, 0), |pos| *pos == goal).map(|route| route.cost),
+            Some(4)
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use crate::error::ParseError;
+use serde::{Serialize, Serializer};
+use std::fmt;
+
+/// The answer to a single part of a day's puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Number(i128),
+    Text(String),
+    /// The part has not been solved (yet).
+    Unsolved,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(val) => write!(f, "{}", val),
+            Answer::Text(val) => write!(f, "{}", val),
+            Answer::Unsolved => write!(f, "-"),
+        }
+    }
+}
+
+/// Answers are written out as a number or string, or as nothing if the part
+/// is unsolved.
+impl Serialize for Answer {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Answer::Number(val) => serializer.serialize_i128(*val),
+            Answer::Text(val) => serializer.serialize_str(val),
+            Answer::Unsolved => serializer.serialize_none(),
+        }
+    }
+}
+
+/// Integers are kept as numbers, unless they are too large for an `i128`, in
+/// which case they are kept as text rather than wrapping round.
+macro_rules! impl_answer_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(val: $t) -> Self {
+                    i128::try_from(val)
+                        .map_or_else(|_| Answer::Text(val.to_string()), Answer::Number)
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from_int!(i32, i64, i128, u32, u64, u128, usize);
+
+impl From<String> for Answer {
+    fn from(val: String) -> Self {
+        Answer::Text(val)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(val: &str) -> Self {
+        Answer::Text(val.to_string())
+    }
+}
+
+/// The two parts of each day's puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+
+    pub fn number(&self) -> u8 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+
+    pub fn from_number(number: u8) -> Option<Part> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+}
+
+/// A day's puzzle solver.
+///
+/// The implementing type holds the parsed puzzle input, so the input is only
+/// parsed once and then shared between both parts.
+pub trait Solution {
+    fn parse(input: &str) -> Result<Self, ParseError>
+    where
+        Self: Sized;
+
+    fn part1(&self) -> Answer;
+
+    fn part2(&self) -> Answer {
+        Answer::Unsolved
+    }
+
+    fn solve(&self, part: Part) -> Answer {
+        match part {
+            Part::One => self.part1(),
+            Part::Two => self.part2(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_from_integers() {
+        assert_eq!(Answer::from(-5), Answer::Number(-5));
+        assert_eq!(Answer::from(i128::MAX as u128), Answer::Number(i128::MAX));
+        assert_eq!(
+            Answer::from(u128::MAX),
+            Answer::Text("340282366920938463463374607431768211455".to_string())
+        );
+    }
+}
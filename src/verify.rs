@@ -0,0 +1,116 @@
+use crate::{
+    answers::{Expected, Manifest},
+    days,
+    input::{InputError, InputSource},
+    runner::run_day,
+    solution::{Answer, Part},
+};
+use std::fmt;
+
+/// The outcome of checking one expected answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Pass,
+    /// The solver gave a different answer to the one expected.
+    Fail {
+        expected: Answer,
+        actual: Answer,
+    },
+    /// The input could not be parsed or read.
+    Error(String),
+    /// The input set doesn't have an input for this day, or the day has not
+    /// been solved.
+    Missing(String),
+}
+
+impl Outcome {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Outcome::Fail { .. } | Outcome::Error(_))
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "PASS"),
+            Outcome::Fail { expected, actual } => {
+                write!(f, "FAIL  expected {}, got {}", expected, actual)
+            }
+            Outcome::Error(reason) => write!(f, "FAIL  {}", reason),
+            Outcome::Missing(reason) => write!(f, "MISSING  {}", reason),
+        }
+    }
+}
+
+/// The result of checking one expected answer from the manifest.
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub set: String,
+    pub day: u8,
+    pub part: Part,
+    pub outcome: Outcome,
+}
+
+/// Runs the solvers against every expected answer in the manifest that
+/// `select` picks out, reading the input for each set and day from `source`.
+/// Each input is only parsed once, however many parts it has answers for.
+pub fn verify(
+    manifest: &Manifest,
+    select: impl Fn(&Expected) -> bool,
+    source: impl Fn(&str, u8) -> InputSource,
+) -> Vec<Check> {
+    let selected: Vec<&Expected> = manifest
+        .expected()
+        .iter()
+        .filter(|exp| select(exp))
+        .collect();
+
+    let mut checks = vec![];
+    for group in selected.chunk_by(|a, b| a.set == b.set && a.day == b.day) {
+        let (set, number) = (&group[0].set, group[0].day);
+        let parts: Vec<Part> = group.iter().map(|exp| exp.part).collect();
+        let outcomes: Vec<Outcome> = match days::get(number) {
+            None => {
+                vec![Outcome::Missing(format!("day {} has not been solved", number)); parts.len()]
+            }
+            Some(day) => match source(set, number).load(number) {
+                Err(InputError::Missing { searched, .. }) => {
+                    let paths: Vec<String> = searched
+                        .iter()
+                        .map(|path| path.display().to_string())
+                        .collect();
+                    vec![Outcome::Missing(format!("no input at {}", paths.join(", "))); parts.len()]
+                }
+                Err(err) => vec![Outcome::Error(err.to_string()); parts.len()],
+                Ok(input) => match run_day(&day, &input, &parts) {
+                    Err(err) => vec![Outcome::Error(err.to_string()); parts.len()],
+                    Ok(results) => group
+                        .iter()
+                        .zip(results)
+                        .map(|(exp, result)| {
+                            if let Some(message) = result.panic {
+                                Outcome::Error(format!("panicked: {}", message))
+                            } else if result.answer == exp.answer {
+                                Outcome::Pass
+                            } else {
+                                Outcome::Fail {
+                                    expected: exp.answer.clone(),
+                                    actual: result.answer,
+                                }
+                            }
+                        })
+                        .collect(),
+                },
+            },
+        };
+
+        checks.extend(group.iter().zip(outcomes).map(|(exp, outcome)| Check {
+            set: exp.set.clone(),
+            day: exp.day,
+            part: exp.part,
+            outcome,
+        }));
+    }
+
+    checks
+}
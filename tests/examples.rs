@@ -0,0 +1,52 @@
+use aoc2023::{
+    answers::{Manifest, MANIFEST_FILE},
+    days::DAYS,
+    input::{InputSource, EXAMPLE_SET_PREFIX},
+    verify::{verify, Outcome},
+};
+use std::path::Path;
+
+fn manifest() -> Manifest {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join(MANIFEST_FILE);
+    Manifest::load(&path).unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Checks the solvers against the answers given for the worked examples in
+/// the puzzles, which are listed in the manifest alongside the real answers.
+#[test]
+fn examples_give_expected_answers() {
+    let checks = verify(
+        &manifest(),
+        |exp| exp.set.starts_with(EXAMPLE_SET_PREFIX),
+        |set, day| InputSource::for_set(set, day, None),
+    );
+
+    let failures: Vec<String> = checks
+        .iter()
+        .filter(|check| check.outcome != Outcome::Pass)
+        .map(|check| {
+            format!(
+                "day {} part {} ({}): {}",
+                check.day,
+                check.part.number(),
+                check.set,
+                check.outcome
+            )
+        })
+        .collect();
+
+    assert!(!checks.is_empty());
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}
+
+#[test]
+fn every_day_has_an_example() {
+    for day in DAYS.iter() {
+        let input = InputSource::for_set("example", day.number, None)
+            .load(day.number)
+            .unwrap_or_else(|err| panic!("{}", err));
+        if let Err(err) = (day.parse)(&input) {
+            panic!("{}", err);
+        }
+    }
+}